@@ -37,7 +37,12 @@ generate_css_vars() {
     done
 }
 
-generate_css_vars
+# Native generator when installed, shell fallback otherwise
+if [ -n "$THEME_NAME" ] && [ -f "$THEME_DIR/colors.toml" ] && command -v cruzalex-themes &>/dev/null; then
+    cruzalex-themes generate "$THEME_NAME" --only waybar >/dev/null
+else
+    generate_css_vars
+fi
 
 # Restart Waybar to apply new CSS (waybar doesn't support live CSS reload)
 if pgrep -x "waybar" > /dev/null; then
@@ -116,4 +116,9 @@ color_yellow = '$c_yellow'"
     fi
 }
 
-update_starship_palette
+# Native generator when installed, shell fallback otherwise
+if [ -n "$THEME_NAME" ] && [ -f "$THEME_DIR/colors.toml" ] && command -v cruzalex-themes &>/dev/null; then
+    cruzalex-themes generate "$THEME_NAME" --only starship >/dev/null
+else
+    update_starship_palette
+fi
@@ -37,7 +37,12 @@ update_mako_colors() {
     sed -i "s/^progress-color=.*/progress-color=over ${ACCENT}66/" "$MAKO_CONF"
 }
 
-update_mako_colors
+# Native generator when installed, shell fallback otherwise
+if [ -n "$THEME_NAME" ] && [ -f "$THEME_DIR/colors.toml" ] && command -v cruzalex-themes &>/dev/null; then
+    cruzalex-themes generate "$THEME_NAME" --only mako >/dev/null
+else
+    update_mako_colors
+fi
 
 # Reload mako (with timeout to prevent hanging)
 if pgrep -x "mako" > /dev/null; then
@@ -63,7 +63,12 @@ set -g window-status-current-format " #I:#W "
 EOF
 }
 
-generate_tmux_theme
+# Native generator when installed, shell fallback otherwise
+if [ -n "$THEME_NAME" ] && [ -f "$THEME_DIR/colors.toml" ] && command -v cruzalex-themes &>/dev/null; then
+    cruzalex-themes generate "$THEME_NAME" --only tmux >/dev/null
+else
+    generate_tmux_theme
+fi
 
 # Reload tmux if running (non-blocking)
 if command -v tmux &>/dev/null && tmux list-sessions &>/dev/null; then
@@ -46,4 +46,9 @@ border = { fg = "$accent" }
 EOF
 }
 
-generate_yazi_theme
+# Native generator when installed, shell fallback otherwise
+if [ -n "$THEME_NAME" ] && [ -f "$THEME_DIR/colors.toml" ] && command -v cruzalex-themes &>/dev/null; then
+    cruzalex-themes generate "$THEME_NAME" --only yazi >/dev/null
+else
+    generate_yazi_theme
+fi
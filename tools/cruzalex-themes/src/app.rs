@@ -1,25 +1,45 @@
 //! Application state and logic
 
+use crate::apply::{self, ApplyReport};
+use crate::catalogue::{catalogue_path, format_age, Catalogue};
+use crate::diagnostics::{self, ParseMode};
+use crate::forge::Repo;
+use crate::github::{self, Fetched, GitHubError, RateLimit, RepoMeta};
+use crate::plan::{self, ApplyPlan};
+use crate::query::Query;
+use crate::similar;
+use crate::sources::{self, ThemeSource};
 use crate::theme::{
-    fetch_github_api_themes, fetch_github_themes, load_local_themes, preview_candidate_urls,
-    Theme, ThemeStatus,
+    self, load_local_themes, normalize_repo_url, preview_candidate_urls, ColorPalette, Theme,
+    ThemeStatus,
 };
+use crate::update::{self, Incoming};
 use anyhow::{Context, Result};
 use image::ImageReader;
 use ratatui::widgets::ListState;
 use ratatui_image::{picker::Picker, protocol::StatefulProtocol};
 use std::collections::{HashMap, HashSet};
-use std::path::PathBuf;
-use std::process::Command;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 use tokio::sync::mpsc;
 
+/// colors.toml downloads running at once during the palette prefetch
+const PALETTE_PREFETCH_CONCURRENCY: usize = 8;
+
+/// git fetches running at once while checking installed themes for updates
+const UPDATE_CHECK_CONCURRENCY: usize = 4;
+
 /// Filter mode for theme list
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
 pub enum FilterMode {
     All,
     Installed,
     Available,
     Favorites,
+    Light,
+    Dark,
+    /// Palette passes the WCAG contrast audit
+    Readable,
 }
 
 impl FilterMode {
@@ -29,6 +49,9 @@ impl FilterMode {
             FilterMode::Installed => "Installed",
             FilterMode::Available => "Available",
             FilterMode::Favorites => "Favorites",
+            FilterMode::Light => "Light",
+            FilterMode::Dark => "Dark",
+            FilterMode::Readable => "Readable",
         }
     }
 
@@ -37,16 +60,21 @@ impl FilterMode {
             FilterMode::All => FilterMode::Installed,
             FilterMode::Installed => FilterMode::Available,
             FilterMode::Available => FilterMode::Favorites,
-            FilterMode::Favorites => FilterMode::All,
+            FilterMode::Favorites => FilterMode::Light,
+            FilterMode::Light => FilterMode::Dark,
+            FilterMode::Dark => FilterMode::Readable,
+            FilterMode::Readable => FilterMode::All,
         }
     }
 }
 
 /// Sort mode for theme list
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
 pub enum SortMode {
     Name,
     Stars,
+    /// Most recently pushed first
+    Updated,
 }
 
 impl SortMode {
@@ -54,13 +82,15 @@ impl SortMode {
         match self {
             SortMode::Name => "Name",
             SortMode::Stars => "Stars",
+            SortMode::Updated => "Updated",
         }
     }
 
     pub fn next(&self) -> Self {
         match self {
             SortMode::Name => SortMode::Stars,
-            SortMode::Stars => SortMode::Name,
+            SortMode::Stars => SortMode::Updated,
+            SortMode::Updated => SortMode::Name,
         }
     }
 }
@@ -68,9 +98,17 @@ impl SortMode {
 /// Background task result
 pub enum TaskResult {
     InstallComplete(String, Result<(), String>),
+    ApplyComplete(String, Result<ApplyReport, String>),
     ImageLoaded(PathBuf, Result<StatefulProtocol, String>),
-    PreviewDownloaded(String, Result<PathBuf, String>),
-    StarsFetched(std::collections::HashMap<String, u32>),
+    /// Cached file and the URL that served it
+    PreviewDownloaded(String, Result<(PathBuf, String), String>),
+    StarsFetched(StarsReport),
+    PaletteFetched(String, Option<ColorPalette>),
+    CatalogueFetched(Result<(Vec<Theme>, String), String>),
+    /// Whether one installed theme's remote branch moved
+    UpdateChecked(String, Result<bool, String>),
+    UpdateFetched(String, Result<Incoming, String>),
+    UpdateApplied(String, Result<(), String>),
 }
 
 /// Application state
@@ -121,14 +159,49 @@ pub struct App {
     pub about_open: bool,
     /// Is the preview zoom modal open?
     pub zoom_open: bool,
+    /// Is the hook results modal open?
+    pub hooks_open: bool,
+    /// Report from the most recent apply
+    pub last_apply: Option<ApplyReport>,
+    /// Is the dry-run plan modal open?
+    pub plan_open: bool,
+    /// Dry-run plan for the selected theme
+    pub plan: Option<ApplyPlan>,
+    /// Remote themes whose colors.toml has been requested
+    palette_requests: HashSet<String>,
+    /// Palettes still outstanding from the post-refresh prefetch
+    palette_prefetch: HashSet<String>,
+    /// Show the "similar themes" side panel?
+    pub show_similar: bool,
+    /// Closest themes to the selection, as (name, distance). Names, since
+    /// `themes` is rebuilt and re-sorted by every catalogue refresh.
+    pub similar: Vec<(String, f64)>,
+    /// Is a background catalogue refresh running?
+    pub refreshing: bool,
+    /// Never touch the network (`--offline`)
+    pub offline: bool,
+    /// GitHub API budget ran out while fetching stars
+    pub rate_limit: Option<RateLimit>,
+    /// Labels of the sources the last refresh read from
+    pub source_labels: Vec<String>,
+    /// Installed themes with commits to pull
+    pub updates: HashSet<String>,
+    /// Update checks still running, and how many remotes couldn't be reached
+    update_checks: usize,
+    update_check_failures: usize,
+    /// Is the incoming-commits modal open?
+    pub update_open: bool,
+    /// Commits updating the selected theme would bring in
+    pub incoming: Option<Incoming>,
+    /// Remote catalogue as last fetched, persisted across runs
+    catalogue: Catalogue,
+    catalogue_path: PathBuf,
 }
 
 impl App {
     /// Create new app instance
     pub async fn new() -> Result<Self> {
-        let config_dir = dirs::config_dir()
-            .context("Could not find config directory")?
-            .join("cruzalex");
+        let config_dir = cruzalex_dir()?;
         let themes_dir = config_dir.join("themes");
 
         // Cache directory for downloaded previews
@@ -180,6 +253,25 @@ impl App {
             image_loading: false,
             about_open: false,
             zoom_open: false,
+            hooks_open: false,
+            last_apply: None,
+            plan_open: false,
+            plan: None,
+            palette_requests: HashSet::new(),
+            palette_prefetch: HashSet::new(),
+            show_similar: false,
+            similar: Vec::new(),
+            refreshing: false,
+            offline: false,
+            rate_limit: None,
+            source_labels: Vec::new(),
+            updates: HashSet::new(),
+            update_checks: 0,
+            update_check_failures: 0,
+            update_open: false,
+            incoming: None,
+            catalogue: Catalogue::default(),
+            catalogue_path: catalogue_path(),
         };
 
         app.update_filter();
@@ -218,6 +310,7 @@ impl App {
 
     /// Update filtered themes based on filter mode, search, and sort
     pub fn update_filter(&mut self) {
+        let query = Query::parse(&self.search_query);
         let mut filtered: Vec<usize> = self
             .themes
             .iter()
@@ -231,21 +324,13 @@ impl App {
                     }
                     FilterMode::Available => matches!(theme.status, ThemeStatus::Available),
                     FilterMode::Favorites => self.favorites.contains(&theme.name),
+                    FilterMode::Light => theme.is_light == Some(true),
+                    FilterMode::Dark => theme.is_light == Some(false),
+                    FilterMode::Readable => theme.contrast.as_ref().is_some_and(|c| c.passes()),
                 };
 
-                // Filter by search
-                let search_match = if self.search_query.is_empty() {
-                    true
-                } else {
-                    theme
-                        .name
-                        .to_lowercase()
-                        .contains(&self.search_query.to_lowercase())
-                        || theme
-                            .display_name
-                            .to_lowercase()
-                            .contains(&self.search_query.to_lowercase())
-                };
+                // Filter by search (free text and color terms)
+                let search_match = query.matches(theme, self.favorites.contains(&theme.name));
 
                 mode_match && search_match
             })
@@ -265,6 +350,20 @@ impl App {
                     stars_b.cmp(&stars_a).then_with(|| self.themes[a].name.cmp(&self.themes[b].name))
                 });
             }
+            SortMode::Updated => {
+                // Newest push first; themes without a known date go last
+                filtered.sort_by(|&a, &b| {
+                    self.themes[b]
+                        .pushed_at
+                        .cmp(&self.themes[a].pushed_at)
+                        .then_with(|| self.themes[a].name.cmp(&self.themes[b].name))
+                });
+            }
+        }
+
+        // While searching, best fuzzy matches first (stable, so ties keep the sort above)
+        if query.has_text() {
+            filtered.sort_by_cached_key(|&i| std::cmp::Reverse(query.score(&self.themes[i])));
         }
 
         self.filtered_themes = filtered;
@@ -412,10 +511,56 @@ impl App {
         self.show_preview = !self.show_preview;
     }
 
+    pub fn toggle_similar(&mut self) {
+        self.show_similar = !self.show_similar;
+        self.refresh_similar();
+    }
+
+    /// Re-rank similar themes for the current selection
+    fn refresh_similar(&mut self) {
+        if !self.show_similar {
+            self.similar.clear();
+            return;
+        }
+        let target = self
+            .list_state
+            .selected()
+            .and_then(|i| self.filtered_themes.get(i).copied());
+        self.similar = match target {
+            Some(idx) => similar::rank_similar(&self.themes, idx, similar::MAX_MATCHES)
+                .into_iter()
+                .map(|(i, distance)| (self.themes[i].name.clone(), distance))
+                .collect(),
+            None => Vec::new(),
+        };
+    }
+
+    /// Select the `n`th (1-based) similar theme, widening the filter if it's hidden
+    pub fn jump_to_similar(&mut self, n: usize) {
+        let Some((name, _)) = self.similar.get(n.wrapping_sub(1)) else {
+            return;
+        };
+        let Some(target) = self.themes.iter().position(|t| &t.name == name) else {
+            return;
+        };
+        if !self.filtered_themes.contains(&target) {
+            self.filter_mode = FilterMode::All;
+            self.search_query.clear();
+            self.update_filter();
+        }
+        if let Some(pos) = self.filtered_themes.iter().position(|&i| i == target) {
+            self.list_state.select(Some(pos));
+            self.load_selected_preview();
+        }
+    }
+
     pub fn toggle_about(&mut self) {
         self.about_open = !self.about_open;
         if self.about_open {
             self.zoom_open = false;
+            self.hooks_open = false;
+            self.plan_open = false;
+            self.update_open = false;
         }
     }
 
@@ -424,14 +569,63 @@ impl App {
             self.zoom_open = !self.zoom_open;
             if self.zoom_open {
                 self.about_open = false;
+                self.hooks_open = false;
+                self.plan_open = false;
+                self.update_open = false;
+            }
+        }
+    }
+
+    /// Compute and show the dry-run plan for the selected theme
+    pub fn show_plan(&mut self) {
+        let Some(theme) = self.selected_theme() else {
+            return;
+        };
+        if theme.status == ThemeStatus::Available {
+            self.status_message = Some("Theme not installed. Press 'i' to install first.".to_string());
+            return;
+        }
+
+        match plan::plan_apply(&self.config_dir, &theme.name) {
+            Ok(plan) => {
+                self.plan = Some(plan);
+                self.about_open = false;
+                self.zoom_open = false;
+                self.hooks_open = false;
+                self.update_open = false;
+                self.plan_open = true;
+            }
+            Err(e) => {
+                self.status_message = Some(format!("Plan failed: {}", e));
             }
         }
     }
 
+    pub fn toggle_hooks(&mut self) {
+        if self.last_apply.is_none() {
+            self.status_message = Some("No theme applied yet this session.".to_string());
+            return;
+        }
+        self.hooks_open = !self.hooks_open;
+        if self.hooks_open {
+            self.about_open = false;
+            self.zoom_open = false;
+            self.plan_open = false;
+            self.update_open = false;
+        }
+    }
+
+    pub fn any_modal_open(&self) -> bool {
+        self.about_open || self.zoom_open || self.hooks_open || self.plan_open || self.update_open
+    }
+
     pub fn close_modals(&mut self) -> bool {
-        if self.about_open || self.zoom_open {
+        if self.any_modal_open() {
             self.about_open = false;
             self.zoom_open = false;
+            self.hooks_open = false;
+            self.plan_open = false;
+            self.update_open = false;
             true
         } else {
             false
@@ -452,56 +646,89 @@ impl App {
         (active, installed, available, self.favorites.len())
     }
 
-    /// Apply selected theme
-    pub fn apply_theme(&mut self) -> Result<()> {
+    /// Apply selected theme (non-blocking)
+    pub fn apply_theme(&mut self) {
         if self.loading {
             self.status_message = Some("Please wait, operation in progress...".to_string());
-            return Ok(());
+            return;
         }
 
         let Some(theme) = self.selected_theme() else {
-            return Ok(());
+            return;
         };
 
-        match theme.status {
-            ThemeStatus::Available => {
-                self.status_message = Some("Theme not installed. Press 'i' to install first.".to_string());
-                return Ok(());
-            }
-            _ => {}
+        if theme.status == ThemeStatus::Available {
+            self.status_message = Some("Theme not installed. Press 'i' to install first.".to_string());
+            return;
         }
 
         let theme_name = theme.name.clone();
+        self.apply_named(theme_name);
+    }
+
+    /// Apply the theme the open dry-run plan was made for, which may no
+    /// longer be the selection
+    pub fn apply_plan(&mut self) {
+        if self.loading {
+            self.status_message = Some("Please wait, operation in progress...".to_string());
+            return;
+        }
+        if let Some(plan) = self.plan.take() {
+            self.apply_named(plan.theme);
+        }
+    }
+
+    fn apply_named(&mut self, theme_name: String) {
+        let config_dir = self.config_dir.clone();
+        let tx = self.task_tx.clone();
+
         self.status_message = Some(format!("Applying theme: {}...", theme_name));
+        self.loading = true;
 
-        // Run cruzalex-theme-set (use full path for reliability)
-        let theme_set_cmd = dirs::config_dir()
-            .map(|d| d.join("cruzalex/bin/cruzalex-theme-set"))
-            .unwrap_or_else(|| std::path::PathBuf::from("cruzalex-theme-set"));
-
-        let output = Command::new(&theme_set_cmd)
-            .arg(&theme_name)
-            .output()
-            .context("Failed to run cruzalex-theme-set")?;
-
-        if output.status.success() {
-            self.status_message = Some(format!("Theme '{}' applied!", theme_name));
-            self.current_theme = Some(theme_name.clone());
-
-            // Update theme statuses
-            for theme in &mut self.themes {
-                if theme.name == theme_name {
-                    theme.status = ThemeStatus::Active;
-                } else if matches!(theme.status, ThemeStatus::Active) {
-                    theme.status = ThemeStatus::Installed;
-                }
-            }
-        } else {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            self.status_message = Some(format!("Failed: {}", stderr.chars().take(50).collect::<String>()));
+        tokio::spawn(async move {
+            let result = apply::apply_theme(
+                &config_dir,
+                &theme_name,
+                apply::DEFAULT_HOOK_TIMEOUT,
+                &apply::hook_log_dir(),
+            )
+            .await
+            .map_err(|e| e.to_string());
+            let _ = tx.send(TaskResult::ApplyComplete(theme_name, result)).await;
+        });
+    }
+
+    /// Roll back a failed apply: restore the previous theme and re-run hooks
+    pub fn revert_apply(&mut self) {
+        if self.loading {
+            self.status_message = Some("Please wait, operation in progress...".to_string());
+            return;
         }
 
-        Ok(())
+        let Some(previous) = self.last_apply.as_ref().and_then(|r| r.revert_target()).cloned() else {
+            self.status_message = Some("Nothing to revert.".to_string());
+            return;
+        };
+
+        let theme_name = previous.display_name();
+        let config_dir = self.config_dir.clone();
+        let tx = self.task_tx.clone();
+
+        self.status_message = Some(format!("Reverting to '{}'...", theme_name));
+        self.loading = true;
+        self.hooks_open = false;
+
+        tokio::spawn(async move {
+            let result = apply::revert(
+                &config_dir,
+                &previous,
+                apply::DEFAULT_HOOK_TIMEOUT,
+                &apply::hook_log_dir(),
+            )
+            .await
+            .map_err(|e| e.to_string());
+            let _ = tx.send(TaskResult::ApplyComplete(theme_name, result)).await;
+        });
     }
 
     /// Install selected theme (non-blocking)
@@ -511,6 +738,11 @@ impl App {
             return;
         }
 
+        if self.offline {
+            self.status_message = Some("Offline: can't install themes.".to_string());
+            return;
+        }
+
         let Some(theme) = self.selected_theme() else {
             return;
         };
@@ -525,6 +757,11 @@ impl App {
             return;
         };
 
+        if let Err(e) = theme::check_name(&theme.name) {
+            self.status_message = Some(e.to_string());
+            return;
+        }
+
         let theme_name = theme.name.clone();
         let url = url.clone();
         let dest = self.themes_dir.join(&theme_name);
@@ -549,6 +786,132 @@ impl App {
         });
     }
 
+    /// Ask each installed git theme's remote whether its branch moved. Only
+    /// the refs are listed, but it's a connection per theme, so this runs on
+    /// refresh rather than at startup.
+    pub fn check_updates(&mut self) {
+        if self.offline || self.update_checks > 0 {
+            return;
+        }
+        let paths: Vec<(String, PathBuf)> = self
+            .themes
+            .iter()
+            .filter_map(|t| Some((t.name.clone(), t.local_path.clone()?)))
+            .filter(|(_, path)| update::is_git_theme(path))
+            .collect();
+        self.update_checks = paths.len();
+        self.update_check_failures = 0;
+
+        let workers = paths.len().min(UPDATE_CHECK_CONCURRENCY);
+        let queue = Arc::new(Mutex::new(paths));
+        for _ in 0..workers {
+            let tx = self.task_tx.clone();
+            let queue = queue.clone();
+            // Detached threads, not spawn_blocking: the runtime waits for
+            // blocking tasks when it shuts down, so a remote that never
+            // answers would keep the app from quitting
+            std::thread::spawn(move || {
+                // Pop in a closure so the lock is released before fetching
+                let next = || queue.lock().ok()?.pop();
+                while let Some((name, path)) = next() {
+                    let result = update::update_available(&path).map_err(|e| format!("{:#}", e));
+                    let _ = tx.blocking_send(TaskResult::UpdateChecked(name, result));
+                }
+            });
+        }
+    }
+
+    /// Fetch the selected theme and show the commits an update would bring in
+    pub fn show_update(&mut self) {
+        if self.loading {
+            self.status_message = Some("Please wait, operation in progress...".to_string());
+            return;
+        }
+        if self.offline {
+            self.status_message = Some("Offline: can't fetch updates.".to_string());
+            return;
+        }
+        let Some(theme) = self.selected_theme() else {
+            return;
+        };
+        let Some(path) = theme.local_path.clone() else {
+            self.status_message = Some("Theme not installed.".to_string());
+            return;
+        };
+        if !update::is_git_theme(&path) {
+            self.status_message = Some("Not a git clone; reinstall it to update.".to_string());
+            return;
+        }
+
+        let theme_name = theme.name.clone();
+        self.status_message = Some(format!("Fetching '{}'...", theme_name));
+        self.loading = true;
+
+        let tx = self.task_tx.clone();
+        tokio::spawn(async move {
+            let result = tokio::task::spawn_blocking(move || update::fetch(&path)).await;
+            let msg = match result {
+                Ok(Ok(incoming)) => Ok(incoming),
+                Ok(Err(e)) => Err(format!("{:#}", e)),
+                Err(e) => Err(format!("Task error: {}", e)),
+            };
+            let _ = tx.send(TaskResult::UpdateFetched(theme_name, msg)).await;
+        });
+    }
+
+    /// Fast-forward to exactly the commits the update modal showed
+    pub fn apply_update(&mut self) {
+        if self.loading {
+            self.status_message = Some("Please wait, operation in progress...".to_string());
+            return;
+        }
+        let Some(incoming) = self.incoming.take() else {
+            return;
+        };
+        self.update_open = false;
+        if !incoming.fast_forward {
+            self.status_message = Some(format!(
+                "'{}' has local commits; update it with git.",
+                incoming.theme
+            ));
+            return;
+        }
+        let path = self.themes_dir.join(&incoming.theme);
+        self.status_message = Some(format!("Updating '{}'...", incoming.theme));
+        self.loading = true;
+
+        let tx = self.task_tx.clone();
+        tokio::spawn(async move {
+            let target = incoming.target;
+            let result =
+                tokio::task::spawn_blocking(move || update::fast_forward(&path, target)).await;
+            let msg = match result {
+                Ok(Ok(())) => Ok(()),
+                Ok(Err(e)) => Err(format!("{:#}", e)),
+                Err(e) => Err(format!("Task error: {}", e)),
+            };
+            let _ = tx.send(TaskResult::UpdateApplied(incoming.theme, msg)).await;
+        });
+    }
+
+    /// Re-read an installed theme after its files changed on disk
+    fn reload_local_theme(&mut self, theme_name: &str) {
+        let Some(theme) = self.themes.iter_mut().find(|t| t.name == theme_name) else {
+            return;
+        };
+        let Some(path) = theme.local_path.clone() else {
+            return;
+        };
+        if let Ok(mut fresh) = Theme::from_local(path, self.current_theme.as_deref()) {
+            fresh.remote_url = theme.remote_url.take();
+            fresh.author = theme.author.take();
+            fresh.stars = theme.stars;
+            *theme = fresh;
+        }
+        self.update_filter();
+        self.load_selected_preview();
+    }
+
     /// Delete selected theme
     pub fn delete_theme(&mut self) -> Result<()> {
         if self.loading {
@@ -587,72 +950,172 @@ impl App {
                 theme.local_path = None;
                 theme.preview_path = None;
                 theme.colors = None;
+                theme.contrast = None;
             }
         }
 
         Ok(())
     }
 
-    /// Refresh themes from GitHub
+    /// Refresh themes from GitHub and wait for the result
     pub async fn refresh_remote_themes(&mut self) -> Result<()> {
+        if self.offline {
+            self.load_cached_catalogue();
+            return Ok(());
+        }
+        let sources = sources::configured()?;
+        self.source_labels = sources.iter().map(|s| s.label()).collect();
         self.status_message = Some("Fetching themes...".to_string());
         self.loading = true;
 
-        let mut remote_themes = match fetch_github_themes().await {
-            Ok(themes) => themes,
+        let result = fetch_catalogue(&sources).await.map_err(|e| e.to_string());
+        self.loading = false;
+        self.finish_refresh(result);
+        Ok(())
+    }
+
+    /// Refresh themes from GitHub without blocking the UI; stars follow once
+    /// the list arrives
+    pub fn refresh_in_background(&mut self) {
+        if self.offline {
+            self.status_message = Some(if self.catalogue.is_empty() {
+                "Offline: no cached catalogue yet".to_string()
+            } else {
+                format!("Offline: catalogue cached {}", format_age(self.catalogue.age()))
+            });
+            return;
+        }
+        if self.refreshing {
+            return;
+        }
+        // Re-read themes.toml so edited sources apply on the next refresh
+        let sources = match sources::configured() {
+            Ok(sources) => sources,
             Err(e) => {
-                self.status_message = Some(format!("Failed to fetch curated list: {}", e));
-                self.loading = false;
-                return Ok(());
+                self.status_message = Some(format!("Failed to read sources: {:#}", e));
+                return;
             }
         };
+        self.source_labels = sources.iter().map(|s| s.label()).collect();
+        self.refreshing = true;
 
-        // Live GitHub topic search — best-effort; rate-limit / network failures
-        // must not break the refresh. Dedup against the curated list by URL so
-        // a repo present in both sources keeps the curated entry's slug.
-        let topic_status = match fetch_github_api_themes().await {
-            Ok(api_themes) => {
-                let known: HashSet<String> = remote_themes
-                    .iter()
-                    .filter_map(|t| t.remote_url.as_deref().map(normalize_repo_url))
-                    .collect();
-                let mut added = 0usize;
-                for t in api_themes {
-                    let dup = t
-                        .remote_url
-                        .as_deref()
-                        .map(|u| known.contains(&normalize_repo_url(u)))
-                        .unwrap_or(true);
-                    if !dup {
-                        remote_themes.push(t);
-                        added += 1;
-                    }
-                }
-                format!(" (+{} via topic)", added)
+        let tx = self.task_tx.clone();
+        tokio::spawn(async move {
+            let result = fetch_catalogue(&sources).await.map_err(|e| e.to_string());
+            let _ = tx.send(TaskResult::CatalogueFetched(result)).await;
+        });
+    }
+
+    /// Show the cached catalogue, if there is one
+    pub fn load_cached_catalogue(&mut self) -> bool {
+        let Some(catalogue) = Catalogue::load(&self.catalogue_path) else {
+            return false;
+        };
+        if catalogue.is_empty() {
+            return false;
+        }
+        let themes = catalogue.themes();
+        let age = format_age(catalogue.age());
+        self.catalogue = catalogue;
+        self.merge_remote_themes(themes);
+        self.status_message = Some(format!(
+            "{} themes (cached {}{})",
+            self.themes.len(),
+            age,
+            if self.offline { ", offline" } else { "" }
+        ));
+        true
+    }
+
+    fn finish_refresh(&mut self, result: Result<(Vec<Theme>, String), String>) {
+        let (mut remote_themes, topic_status) = match result {
+            Ok(fetched) => fetched,
+            Err(e) => {
+                self.status_message = Some(if self.catalogue.is_empty() {
+                    format!("Failed to fetch themes: {}", e)
+                } else {
+                    format!(
+                        "Failed to fetch themes: {} (showing cache from {})",
+                        e,
+                        format_age(self.catalogue.age())
+                    )
+                });
+                return;
             }
-            Err(_) => String::from(" (topic search skipped)"),
         };
 
-        let installed_names: HashSet<String> = self
-            .themes
-            .iter()
-            .filter(|t| matches!(t.status, ThemeStatus::Active | ThemeStatus::Installed))
-            .map(|t| t.name.clone())
-            .collect();
+        self.catalogue = Catalogue::from_fetch(&mut remote_themes, &self.catalogue);
+        self.save_catalogue();
+        self.merge_remote_themes(remote_themes);
+        self.status_message =
+            Some(format!("Found {} themes{}", self.themes.len(), topic_status));
+    }
+
+    /// Re-run the filter after themes changed, keeping the selected theme
+    fn refilter(&mut self) {
+        let selected = self.selected_theme().map(|t| t.name.clone());
+        self.update_filter();
+        let position = selected
+            .and_then(|name| self.filtered_themes.iter().position(|&i| self.themes[i].name == name));
+        if let Some(pos) = position {
+            self.list_state.select(Some(pos));
+        }
+        self.refresh_similar();
+    }
 
-        for remote in remote_themes {
-            if !installed_names.contains(&remote.name) {
-                self.themes.push(remote);
+    /// Replace the remote (not installed) themes, keeping the selection and
+    /// anything already fetched for them this session
+    fn merge_remote_themes(&mut self, remote_themes: Vec<Theme>) {
+        let selected = self.selected_theme().map(|t| t.name.clone());
+
+        let mut previous: HashMap<String, Theme> = HashMap::new();
+        self.themes.retain(|t| {
+            if matches!(t.status, ThemeStatus::Available) {
+                previous.insert(t.name.clone(), t.clone());
+                false
+            } else {
+                true
+            }
+        });
+        let installed_names: HashSet<String> = self.themes.iter().map(|t| t.name.clone()).collect();
+
+        for mut remote in remote_themes {
+            if installed_names.contains(&remote.name) {
+                continue;
+            }
+            if let Some(old) = previous.remove(&remote.name) {
+                if let Some(colors) = old.colors {
+                    remote.set_remote_palette(colors);
+                }
+                remote.preview_path = old.preview_path;
             }
+            self.themes.push(remote);
         }
 
         self.themes.sort_by(|a, b| a.name.cmp(&b.name));
         self.update_filter();
-        self.status_message =
-            Some(format!("Found {} themes{}", self.themes.len(), topic_status));
 
-        self.loading = false;
-        Ok(())
+        let position = selected
+            .as_ref()
+            .and_then(|name| self.filtered_themes.iter().position(|&i| &self.themes[i].name == name));
+        match position {
+            Some(pos) => {
+                self.list_state.select(Some(pos));
+                self.refresh_similar();
+            }
+            None => {
+                if !self.filtered_themes.is_empty() {
+                    self.list_state.select(Some(0));
+                }
+                self.load_selected_preview();
+            }
+        }
+    }
+
+    fn save_catalogue(&mut self) {
+        if let Err(e) = self.catalogue.save(&self.catalogue_path) {
+            self.status_message = Some(format!("Could not write catalogue cache: {}", e));
+        }
     }
 
     /// Process background task results
@@ -691,6 +1154,119 @@ impl App {
                         }
                     }
                 }
+                TaskResult::UpdateChecked(theme_name, res) => {
+                    self.update_checks = self.update_checks.saturating_sub(1);
+                    match res {
+                        Ok(true) => {
+                            self.updates.insert(theme_name);
+                        }
+                        Ok(false) => {
+                            self.updates.remove(&theme_name);
+                        }
+                        // No marker; `U` on the theme shows the actual error
+                        Err(_) => self.update_check_failures += 1,
+                    }
+                    if self.update_checks == 0 && !self.updates.is_empty() {
+                        let mut msg = format!(
+                            "{} installed theme(s) have updates (U to review)",
+                            self.updates.len()
+                        );
+                        if self.update_check_failures > 0 {
+                            msg.push_str(&format!(
+                                ", {} remote(s) unreachable",
+                                self.update_check_failures
+                            ));
+                        }
+                        self.status_message = Some(msg);
+                    }
+                }
+                TaskResult::UpdateFetched(theme_name, res) => {
+                    self.loading = false;
+                    match res {
+                        Ok(incoming) if incoming.commits.is_empty() => {
+                            self.updates.remove(&theme_name);
+                            self.status_message = Some(format!("'{}' is up to date.", theme_name));
+                        }
+                        Ok(incoming) => {
+                            self.status_message = None;
+                            self.incoming = Some(incoming);
+                            self.about_open = false;
+                            self.zoom_open = false;
+                            self.hooks_open = false;
+                            self.plan_open = false;
+                            self.update_open = true;
+                        }
+                        Err(e) => {
+                            self.status_message = Some(format!("Fetch failed: {}", e));
+                        }
+                    }
+                }
+                TaskResult::UpdateApplied(theme_name, res) => {
+                    self.loading = false;
+                    match res {
+                        Ok(()) => {
+                            self.updates.remove(&theme_name);
+                            self.reload_local_theme(&theme_name);
+                            let hint = if self.current_theme.as_deref() == Some(theme_name.as_str()) {
+                                " Press Enter to re-apply it."
+                            } else {
+                                ""
+                            };
+                            self.status_message = Some(format!("Theme '{}' updated.{}", theme_name, hint));
+                        }
+                        Err(e) => {
+                            self.status_message = Some(format!("Update failed: {}", e));
+                        }
+                    }
+                }
+                TaskResult::ApplyComplete(theme_name, res) => {
+                    self.loading = false;
+                    match res {
+                        Ok(report) => {
+                            let failed = report.failed_hooks();
+                            let verb = if report.reverted { "restored" } else { "applied" };
+                            self.status_message = Some(if failed == 0 {
+                                format!("Theme '{}' {}!", theme_name, verb)
+                            } else if report.revert_target().is_some() {
+                                format!(
+                                    "Theme '{}' applied, {} of {} hooks failed (u to revert, h for details)",
+                                    theme_name,
+                                    failed,
+                                    report.hooks.len()
+                                )
+                            } else {
+                                format!(
+                                    "Theme '{}' {}, {} of {} hooks failed (h for details)",
+                                    theme_name,
+                                    verb,
+                                    failed,
+                                    report.hooks.len()
+                                )
+                            });
+                            // Surface failures immediately; successes stay one key away
+                            if failed > 0 {
+                                self.about_open = false;
+                                self.zoom_open = false;
+                                self.plan_open = false;
+                                self.hooks_open = true;
+                            }
+                            self.last_apply = Some(report);
+                            self.current_theme = Some(theme_name.clone());
+
+                            // Update theme statuses
+                            for theme in &mut self.themes {
+                                if theme.name == theme_name {
+                                    theme.status = ThemeStatus::Active;
+                                } else if matches!(theme.status, ThemeStatus::Active) {
+                                    theme.status = ThemeStatus::Installed;
+                                }
+                            }
+                        }
+                        Err(e) => {
+                            self.status_message = Some(format!("Apply failed: {}", e));
+                        }
+                    }
+                }
                 TaskResult::ImageLoaded(path, res) => {
                     self.image_loading = false;
                     // Only use the image if it's still the one we're expecting
@@ -700,11 +1276,15 @@ impl App {
                 }
                 TaskResult::PreviewDownloaded(theme_name, res) => {
                     match res {
-                        Ok(cached_path) => {
+                        Ok((cached_path, url)) => {
                             // Update the theme's preview_path with the cached file
                             if let Some(theme) = self.themes.iter_mut().find(|t| t.name == theme_name) {
                                 theme.preview_path = Some(cached_path.clone());
+                                theme.preview_url = Some(url.clone());
                             }
+                            // Remember which candidate worked so the next run tries it first
+                            self.catalogue.set_preview_url(&theme_name, Some(url));
+                            self.save_catalogue();
                             // If this is the currently selected theme, trigger image load
                             if let Some(selected) = self.selected_theme() {
                                 if selected.name == theme_name {
@@ -718,6 +1298,8 @@ impl App {
                             if let Some(theme) = self.themes.iter_mut().find(|t| t.name == theme_name) {
                                 theme.preview_url = None;
                             }
+                            self.catalogue.set_preview_url(&theme_name, None);
+                            self.save_catalogue();
                             // Clear loading state if this was for the current theme
                             if let Some(selected) = self.selected_theme() {
                                 if selected.name == theme_name {
@@ -727,12 +1309,36 @@ impl App {
                         }
                     }
                 }
-                TaskResult::StarsFetched(stars_map) => {
-                    // Update stars for all themes
-                    for theme in &mut self.themes {
-                        if let Some(&stars) = stars_map.get(&theme.name) {
-                            theme.stars = Some(stars);
+                TaskResult::StarsFetched(report) => {
+                    self.apply_stars(report);
+                }
+                TaskResult::PaletteFetched(theme_name, colors) => {
+                    if let Some(colors) = colors {
+                        self.catalogue.set_colors(&theme_name, &colors);
+                        if let Some(theme) = self.themes.iter_mut().find(|t| t.name == theme_name) {
+                            if theme.colors.is_none() {
+                                theme.set_remote_palette(colors);
+                            }
                         }
+                        self.refresh_similar();
+                    }
+                    let prefetched = self.palette_prefetch.remove(&theme_name);
+                    if !prefetched {
+                        self.save_catalogue();
+                    } else if self.palette_prefetch.is_empty() {
+                        // Light/Dark, Readable and color searches can now
+                        // see the whole catalogue
+                        self.save_catalogue();
+                        self.refilter();
+                    }
+                }
+                TaskResult::CatalogueFetched(res) => {
+                    self.refreshing = false;
+                    let fetched = res.is_ok();
+                    self.finish_refresh(res);
+                    if fetched {
+                        self.fetch_stars();
+                        self.prefetch_palettes();
                     }
                 }
             }
@@ -741,38 +1347,81 @@ impl App {
     }
 
     /// Fetch GitHub stars for all themes in background
-    pub fn fetch_stars(&self) {
+    pub fn fetch_stars(&mut self) {
+        if self.offline || self.rate_limited() {
+            return;
+        }
         let tx = self.task_tx.clone();
-        let themes: Vec<(String, Option<String>)> = self.themes
-            .iter()
-            .filter(|t| t.remote_url.is_some())
-            .map(|t| (t.name.clone(), t.remote_url.clone()))
-            .collect();
+        let themes = self.star_targets();
 
         tokio::spawn(async move {
-            let mut stars_map = std::collections::HashMap::new();
-
-            let client = reqwest::Client::builder()
-                .timeout(std::time::Duration::from_secs(30))
-                .build()
-                .unwrap_or_default();
-
-            // Fetch stars using GitHub search API (more efficient than individual requests)
-            // Rate limit is 10 requests/minute for unauthenticated, so batch them
-            for chunk in themes.chunks(30) {
-                for (name, url) in chunk {
-                    if let Some(url) = url {
-                        if let Ok(stars) = fetch_repo_stars(&client, url).await {
-                            stars_map.insert(name.clone(), stars);
-                        }
-                    }
-                }
-                // Small delay between batches to avoid rate limiting
-                tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+            let report = fetch_all_stars(themes).await;
+            let _ = tx.send(TaskResult::StarsFetched(report)).await;
+        });
+    }
+
+    /// Fetch GitHub stars for all themes and wait for the result
+    pub async fn fetch_stars_now(&mut self) {
+        if self.offline || self.rate_limited() {
+            return;
+        }
+        let report = fetch_all_stars(self.star_targets()).await;
+        self.apply_stars(report);
+    }
+
+    /// Is the GitHub API known to refuse requests right now?
+    ///
+    /// Sets the status message so the user knows why stars aren't updating.
+    fn rate_limited(&mut self) -> bool {
+        match self.rate_limit.filter(RateLimit::active) {
+            Some(limit) => {
+                self.status_message = Some(format!("GitHub rate limited until {}", limit.reset_time()));
+                true
             }
+            None => false,
+        }
+    }
 
-            let _ = tx.send(TaskResult::StarsFetched(stars_map)).await;
-        });
+    /// Themes with a remote URL that stars can be fetched for, with the ETag
+    /// of their cached stars
+    fn star_targets(&self) -> Vec<StarTarget> {
+        self.themes
+            .iter()
+            .filter_map(|t| {
+                Some(StarTarget {
+                    name: t.name.clone(),
+                    url: t.remote_url.clone()?,
+                    etag: self.catalogue.etag(&t.name).map(str::to_string),
+                })
+            })
+            .collect()
+    }
+
+    fn apply_stars(&mut self, report: StarsReport) {
+        for theme in &mut self.themes {
+            if let Some(meta) = report.metadata.get(&theme.name) {
+                theme.set_repo_meta(meta);
+            } else if let Some(&stars) = report.stars.get(&theme.name) {
+                theme.stars = Some(stars);
+            }
+        }
+        self.catalogue.set_stars(&report.stars, &report.etags);
+        self.catalogue.set_repo_meta(&report.metadata);
+        self.save_catalogue();
+        if let Some(limit) = report.rate_limited {
+            let hint = if github::token().is_some() {
+                ""
+            } else {
+                " (set GITHUB_TOKEN to raise the limit)"
+            };
+            self.status_message = Some(format!(
+                "GitHub rate limited until {}; {} stars updated{}",
+                limit.reset_time(),
+                report.stars.len(),
+                hint
+            ));
+            self.rate_limit = Some(limit);
+        }
     }
 
     /// Initialize the image picker for terminal graphics protocol detection
@@ -791,8 +1440,62 @@ impl App {
         }
     }
 
+    /// Fetch colors.toml for every remote theme without a cached palette,
+    /// a few at a time. Until this finishes, filters that read the palette
+    /// (Light/Dark, Readable) only see themes whose palette is known.
+    pub fn prefetch_palettes(&mut self) {
+        if self.offline {
+            return;
+        }
+        let targets: Vec<(String, String)> = self
+            .themes
+            .iter()
+            .filter(|t| t.status == ThemeStatus::Available && t.colors.is_none())
+            .filter(|t| !self.palette_requests.contains(&t.name))
+            .filter_map(|t| Some((t.name.clone(), t.remote_url.clone()?)))
+            .collect();
+
+        let limit = Arc::new(tokio::sync::Semaphore::new(PALETTE_PREFETCH_CONCURRENCY));
+        for (name, url) in targets {
+            self.palette_requests.insert(name.clone());
+            self.palette_prefetch.insert(name.clone());
+            let tx = self.task_tx.clone();
+            let limit = limit.clone();
+            tokio::spawn(async move {
+                let _permit = limit.acquire().await;
+                let colors = fetch_remote_palette(&url).await;
+                let _ = tx.send(TaskResult::PaletteFetched(name, colors)).await;
+            });
+        }
+    }
+
+    /// Fetch colors.toml for the selected remote theme, once per theme,
+    /// so its swatches and contrast audit show before installing
+    fn fetch_selected_palette(&mut self) {
+        let Some(theme) = self.selected_theme() else {
+            return;
+        };
+        if self.offline || theme.colors.is_some() || self.palette_requests.contains(&theme.name) {
+            return;
+        }
+        let Some(url) = theme.remote_url.clone() else {
+            return;
+        };
+        let name = theme.name.clone();
+        self.palette_requests.insert(name.clone());
+
+        let tx = self.task_tx.clone();
+        tokio::spawn(async move {
+            let colors = fetch_remote_palette(&url).await;
+            let _ = tx.send(TaskResult::PaletteFetched(name, colors)).await;
+        });
+    }
+
     /// Load preview image for the currently selected theme
     pub fn load_selected_preview(&mut self) {
+        self.fetch_selected_palette();
+        self.refresh_similar();
+
         let Some(theme) = self.selected_theme() else {
             self.current_preview_image = None;
             self.current_preview_path = None;
@@ -818,7 +1521,7 @@ impl App {
             self.image_loading = true;
 
             let tx = self.task_tx.clone();
-            let mut picker = picker.clone();
+            let mut picker = *picker;
             let path_for_task = preview_path.clone();
             let path_for_send = preview_path;
 
@@ -851,7 +1554,7 @@ impl App {
                 self.image_loading = true;
 
                 let tx = self.task_tx.clone();
-                let mut picker = picker.clone();
+                let mut picker = *picker;
                 let path_for_task = cached_path.clone();
                 let path_for_send = cached_path;
 
@@ -868,6 +1571,9 @@ impl App {
 
                     let _ = tx.send(TaskResult::ImageLoaded(path_for_send, msg)).await;
                 });
+            } else if self.offline {
+                self.current_preview_image = None;
+                self.current_preview_path = None;
             } else {
                 // Not cached, need to download
                 self.current_preview_image = None;
@@ -890,62 +1596,274 @@ impl App {
     }
 }
 
-/// Fetch stars for a single repo
-async fn fetch_repo_stars(client: &reqwest::Client, github_url: &str) -> Result<u32, String> {
-    let url = github_url.trim_end_matches(".git");
-    let parts: Vec<&str> = url.split("github.com/").collect();
-    if parts.len() != 2 {
-        return Err("Invalid GitHub URL".to_string());
+/// Every configured source merged, with a status suffix
+///
+/// Sources are best-effort: one that fails (rate limit, network, a bad
+/// index) is reported and skipped, and only all of them failing is an
+/// error. Earlier sources win duplicates, by URL and then by name, so a
+/// repo in both the curated list and a topic search keeps the curated slug.
+async fn fetch_catalogue(sources: &[Box<dyn ThemeSource>]) -> Result<(Vec<Theme>, String)> {
+    let mut remote_themes: Vec<Theme> = Vec::new();
+    let mut urls = HashSet::new();
+    let mut names = HashSet::new();
+    let mut notes = Vec::new();
+    let mut last_error = None;
+    let mut any_ok = false;
+
+    for (i, source) in sources.iter().enumerate() {
+        match source.fetch().await {
+            Ok(listing) => {
+                any_ok = true;
+                let mut added = 0usize;
+                for t in listing.themes {
+                    let url = t.remote_url.as_deref().map(normalize_repo_url);
+                    if url.as_ref().is_some_and(|u| urls.contains(u)) || names.contains(&t.name) {
+                        continue;
+                    }
+                    urls.extend(url);
+                    names.insert(t.name.clone());
+                    remote_themes.push(t);
+                    added += 1;
+                }
+                // The first source is the baseline; later ones say what they added
+                if i > 0 {
+                    notes.push(format!("+{} via {}", added, source.label()));
+                }
+                if !listing.failures.is_empty() {
+                    notes.push(format!(
+                        "{} partial: {}",
+                        source.label(),
+                        listing.failures.join("; ")
+                    ));
+                }
+            }
+            Err(e) => {
+                notes.push(match e.downcast_ref::<GitHubError>() {
+                    Some(limited @ GitHubError::RateLimited(_)) => {
+                        format!("{} skipped: {}", source.label(), limited)
+                    }
+                    _ => format!("{} skipped", source.label()),
+                });
+                last_error = Some(e.context(format!("failed to fetch {}", source.label())));
+            }
+        }
+    }
+
+    if let (false, Some(e)) = (any_ok, last_error) {
+        return Err(e);
     }
-    let repo_path = parts[1];
+    let status = if notes.is_empty() {
+        String::new()
+    } else {
+        format!(" ({})", notes.join(", "))
+    };
+    Ok((remote_themes, status))
+}
+
+/// A repo to fetch stars for
+struct StarTarget {
+    name: String,
+    url: String,
+    /// From the cached response, to revalidate instead of refetch
+    etag: Option<String>,
+}
 
-    let api_url = format!("https://api.github.com/repos/{}", repo_path);
+/// What a stars refresh learned
+#[derive(Debug, Default)]
+pub struct StarsReport {
+    /// Changed star counts; unchanged (304) repos keep their cached value
+    stars: HashMap<String, u32>,
+    etags: HashMap<String, String>,
+    /// Full repo metadata, by theme name (GraphQL only)
+    metadata: HashMap<String, RepoMeta>,
+    /// Set when the run stopped early because the API budget ran out
+    rate_limited: Option<RateLimit>,
+}
+
+/// Fetch stars for every target, skipping repos that fail
+///
+/// GitHub repos share one rate-limited budget (see [`fetch_github_stars`]);
+/// repos on other forges get one metadata request each, and those on plain
+/// git hosts have nothing to fetch.
+async fn fetch_all_stars(targets: Vec<StarTarget>) -> StarsReport {
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(30))
+        .build()
+        .unwrap_or_default();
 
-    let response = client
-        .get(&api_url)
-        .header("Accept", "application/vnd.github+json")
-        .header("User-Agent", "cruzalex-themes/0.1")
-        .send()
-        .await
-        .map_err(|e| e.to_string())?;
+    let (on_github, elsewhere): (Vec<_>, Vec<_>) = targets
+        .into_iter()
+        .partition(|t| Repo::parse(&t.url).is_some_and(|r| r.github_path().is_some()));
+    let mut report = fetch_github_stars(&client, on_github).await;
 
-    if !response.status().is_success() {
-        return Err(format!("GitHub API error: {}", response.status()));
+    for target in elsewhere {
+        let Some(repo) = Repo::parse(&target.url) else {
+            continue;
+        };
+        if let Some(meta) = repo.fetch_metadata(&client).await {
+            report.stars.insert(target.name.clone(), meta.stars);
+            report.metadata.insert(target.name, meta);
+        }
     }
+    report
+}
 
+/// Stars for GitHub repos, stopping as soon as the rate limit is exhausted.
+///
+/// With a token this is a handful of GraphQL requests that also bring the
+/// rest of the repo metadata; without one, one REST request per repo.
+async fn fetch_github_stars(client: &reqwest::Client, targets: Vec<StarTarget>) -> StarsReport {
+    if github::token().is_some() {
+        if let Some(report) = fetch_all_metadata(client, &targets).await {
+            return report;
+        }
+    }
+
+    let mut report = StarsReport::default();
+
+    for target in targets {
+        let Some(repo) = Repo::parse(&target.url) else {
+            continue;
+        };
+        let (result, limit) = fetch_repo_stars(client, &repo, target.etag.as_deref()).await;
+        match result {
+            Ok(Fetched::Modified(stars, etag)) => {
+                report.stars.insert(target.name.clone(), stars);
+                if let Some(etag) = etag {
+                    report.etags.insert(target.name, etag);
+                }
+            }
+            Ok(Fetched::NotModified) => {}
+            Err(GitHubError::RateLimited(limit)) => {
+                report.rate_limited = Some(limit);
+                break;
+            }
+            Err(_) => {}
+        }
+        if let Some(limit) = limit.filter(RateLimit::exhausted) {
+            report.rate_limited = Some(limit);
+            break;
+        }
+    }
+
+    report
+}
+
+/// Download and parse a remote theme's colors.toml
+async fn fetch_remote_palette(repo_url: &str) -> Option<ColorPalette> {
+    let repo = Repo::parse(repo_url)?;
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(8))
+        .build()
+        .ok()?;
+
+    for url in repo.raw_file_urls("colors.toml") {
+        let response = match repo.authorize(client.get(&url)).send().await {
+            Ok(r) if r.status().is_success() => r,
+            _ => continue,
+        };
+        if let Ok(content) = response.text().await {
+            return diagnostics::load_colors(&content, ParseMode::Lenient).0;
+        }
+    }
+    None
+}
+
+/// Stars and metadata in batches of [`github::GRAPHQL_BATCH`] repos.
+///
+/// None when the first batch fails outright (e.g. a revoked token), so the
+/// caller can fall back to REST.
+async fn fetch_all_metadata(client: &reqwest::Client, targets: &[StarTarget]) -> Option<StarsReport> {
+    let repos: Vec<(String, &str)> = targets
+        .iter()
+        .filter_map(|t| Some((Repo::parse(&t.url)?.github_path()?.to_string(), t.name.as_str())))
+        .collect();
+
+    let mut report = StarsReport::default();
+    for (batch, chunk) in repos.chunks(github::GRAPHQL_BATCH).enumerate() {
+        let paths: Vec<&str> = chunk.iter().map(|(path, _)| path.as_str()).collect();
+        let (result, limit) = github::fetch_repo_metadata(client, &paths).await;
+        match result {
+            Ok(metadata) => {
+                for (path, name) in chunk {
+                    if let Some(meta) = metadata.get(path) {
+                        report.stars.insert(name.to_string(), meta.stars);
+                        report.metadata.insert(name.to_string(), meta.clone());
+                    }
+                }
+            }
+            Err(GitHubError::RateLimited(limit)) => {
+                report.rate_limited = Some(limit);
+                break;
+            }
+            Err(_) if batch == 0 => return None,
+            Err(_) => {}
+        }
+        if let Some(limit) = limit.filter(RateLimit::exhausted) {
+            report.rate_limited = Some(limit);
+            break;
+        }
+    }
+    Some(report)
+}
+
+/// Fetch stars for a single GitHub repo, conditionally when `etag` is known
+async fn fetch_repo_stars(
+    client: &reqwest::Client,
+    repo: &Repo,
+    etag: Option<&str>,
+) -> (Result<Fetched<u32>, GitHubError>, Option<RateLimit>) {
     #[derive(serde::Deserialize)]
     struct RepoInfo {
         stargazers_count: u32,
     }
 
-    let info: RepoInfo = response.json().await.map_err(|e| e.to_string())?;
-    Ok(info.stargazers_count)
+    let Some(api_url) = repo.metadata_url() else {
+        return (Err(GitHubError::Status(reqwest::StatusCode::NOT_FOUND)), None);
+    };
+
+    let (result, limit) = github::get_json::<RepoInfo>(client, &api_url, &[], etag).await;
+    let result = result.map(|fetched| match fetched {
+        Fetched::Modified(info, etag) => Fetched::Modified(info.stargazers_count, etag),
+        Fetched::NotModified => Fetched::NotModified,
+    });
+    (result, limit)
 }
 
 /// Download preview image from a list of candidate URLs and cache it.
 ///
-/// `seed_url` is any URL that points at the GitHub repo (raw or repo page);
+/// `seed_url` is any URL that points at the repo (raw or repo page);
 /// we derive a fan-out of likely preview paths from it and try each. The first
 /// candidate that returns actual image bytes wins. A 200 OK with HTML body
 /// (GitHub Pages 404 fallback) is rejected via magic-byte sniffing so we don't
 /// cache garbage and stop probing.
 async fn download_preview(
     seed_url: &str,
-    cache_dir: &PathBuf,
+    cache_dir: &Path,
     theme_name: &str,
-) -> Result<PathBuf, String> {
+) -> Result<(PathBuf, String), String> {
     let client = reqwest::Client::builder()
         .timeout(std::time::Duration::from_secs(8))
         .build()
         .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
 
-    let candidates = preview_candidate_urls(seed_url);
+    let mut candidates = preview_candidate_urls(seed_url);
     if candidates.is_empty() {
         return Err("No candidate URLs derivable from seed".to_string());
     }
+    // A seed resolved on an earlier run (see the catalogue cache) goes first
+    if let Some(pos) = candidates.iter().position(|c| c == seed_url) {
+        let seed = candidates.remove(pos);
+        candidates.insert(0, seed);
+    } else if looks_like_image_url(seed_url) {
+        candidates.insert(0, seed_url.to_string());
+    }
+    let Some(repo) = Repo::parse(seed_url) else {
+        return Err("No candidate URLs derivable from seed".to_string());
+    };
 
     for try_url in &candidates {
-        let response = match client.get(try_url).send().await {
+        let response = match repo.authorize(client.get(try_url)).send().await {
             Ok(r) if r.status().is_success() => r,
             _ => continue,
         };
@@ -959,12 +1877,17 @@ async fn download_preview(
         let cached_path = cache_dir.join(format!("{}.png", theme_name));
         std::fs::write(&cached_path, &bytes)
             .map_err(|e| format!("Failed to write cache file: {}", e))?;
-        return Ok(cached_path);
+        return Ok((cached_path, try_url.clone()));
     }
 
     Err("No preview image found among candidates".to_string())
 }
 
+fn looks_like_image_url(url: &str) -> bool {
+    let url = url.to_lowercase();
+    [".png", ".jpg", ".jpeg", ".webp", ".gif"].iter().any(|ext| url.ends_with(ext))
+}
+
 fn looks_like_image(bytes: &[u8]) -> bool {
     bytes.starts_with(b"\x89PNG\r\n\x1a\n")
         || bytes.starts_with(b"\xff\xd8\xff")
@@ -973,7 +1896,7 @@ fn looks_like_image(bytes: &[u8]) -> bool {
 }
 
 /// Load and prepare a preview image for display
-fn load_preview_image(picker: &mut Picker, path: &PathBuf) -> Result<StatefulProtocol, String> {
+fn load_preview_image(picker: &mut Picker, path: &Path) -> Result<StatefulProtocol, String> {
     // .with_guessed_format() lets us decode JPEG/WebP/GIF bytes even when the
     // cached file ends in .png — see download_preview, which always writes the
     // canonical .png path regardless of source content type.
@@ -987,8 +1910,18 @@ fn load_preview_image(picker: &mut Picker, path: &PathBuf) -> Result<StatefulPro
     Ok(protocol)
 }
 
+/// Root of the cruzalex config tree (`$CRUZALEX_DIR`, else ~/.config/cruzalex)
+pub fn cruzalex_dir() -> Result<PathBuf> {
+    if let Some(dir) = std::env::var_os("CRUZALEX_DIR") {
+        return Ok(PathBuf::from(dir));
+    }
+    Ok(dirs::config_dir()
+        .context("Could not find config directory")?
+        .join("cruzalex"))
+}
+
 /// Load favorites from file
-fn load_favorites(config_dir: &PathBuf) -> HashSet<String> {
+fn load_favorites(config_dir: &Path) -> HashSet<String> {
     let favorites_file = config_dir.join(".favorites");
     if let Ok(content) = std::fs::read_to_string(&favorites_file) {
         content.lines().map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect()
@@ -998,15 +1931,8 @@ fn load_favorites(config_dir: &PathBuf) -> HashSet<String> {
 }
 
 /// Save favorites to file
-fn save_favorites(config_dir: &PathBuf, favorites: &HashSet<String>) {
+fn save_favorites(config_dir: &Path, favorites: &HashSet<String>) {
     let favorites_file = config_dir.join(".favorites");
     let content: String = favorites.iter().map(|s| s.as_str()).collect::<Vec<_>>().join("\n");
     let _ = std::fs::write(favorites_file, content);
 }
-
-fn normalize_repo_url(url: &str) -> String {
-    url.trim()
-        .trim_end_matches('/')
-        .trim_end_matches(".git")
-        .to_lowercase()
-}
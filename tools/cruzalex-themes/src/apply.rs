@@ -0,0 +1,524 @@
+//! Native theme application (replaces the cruzalex-theme-set script)
+
+use crate::diagnostics::parse_color_lines;
+use crate::palette;
+use crate::theme;
+use anyhow::{bail, Context, Result};
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+use tokio::process::Command;
+use tokio::task::JoinSet;
+
+/// Default per-hook timeout, same as `timeout 3` in the shell script
+pub const DEFAULT_HOOK_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Number of trailing output lines kept per stream
+const OUTPUT_TAIL_LINES: usize = 10;
+
+/// How a single hook run ended
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HookOutcome {
+    /// Exited with status 0
+    Success,
+    /// Exited non-zero (None if killed by a signal)
+    Failed(Option<i32>),
+    /// Killed after exceeding the timeout
+    TimedOut,
+    /// Could not be started at all
+    SpawnError(String),
+}
+
+impl HookOutcome {
+    pub fn label(&self) -> String {
+        match self {
+            HookOutcome::Success => "ok".to_string(),
+            HookOutcome::Failed(Some(code)) => format!("exit {}", code),
+            HookOutcome::Failed(None) => "killed by signal".to_string(),
+            HookOutcome::TimedOut => "timed out".to_string(),
+            HookOutcome::SpawnError(e) => format!("spawn error: {}", e),
+        }
+    }
+}
+
+/// Result of running one hook
+#[derive(Debug, Clone)]
+pub struct HookResult {
+    /// Hook file name (e.g. `20-waybar.sh`)
+    pub name: String,
+    pub outcome: HookOutcome,
+    pub duration: Duration,
+    /// Last lines of stdout
+    pub stdout: String,
+    /// Last lines of stderr
+    pub stderr: String,
+}
+
+impl HookResult {
+    pub fn succeeded(&self) -> bool {
+        self.outcome == HookOutcome::Success
+    }
+}
+
+/// What `current` and `.current-theme` pointed at before an apply
+#[derive(Debug, Clone)]
+pub struct PreviousTheme {
+    /// Contents of `.current-theme`, if it existed
+    pub name: Option<String>,
+    /// Target of the `current` symlink
+    pub target: PathBuf,
+}
+
+impl PreviousTheme {
+    /// Snapshot the current state; None if `current` is not a symlink
+    pub fn capture(cruzalex_dir: &Path) -> Option<Self> {
+        let target = std::fs::read_link(cruzalex_dir.join("current")).ok()?;
+        let name = std::fs::read_to_string(cruzalex_dir.join(".current-theme"))
+            .ok()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty());
+        Some(Self { name, target })
+    }
+
+    /// Theme name to report, falling back to the symlink target's directory name
+    pub fn display_name(&self) -> String {
+        self.name.clone().unwrap_or_else(|| {
+            self.target
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default()
+        })
+    }
+}
+
+/// Result of applying a theme
+#[derive(Debug, Clone)]
+pub struct ApplyReport {
+    pub theme: String,
+    /// Hook results, ordered by hook name
+    pub hooks: Vec<HookResult>,
+    /// State before this apply, for rollback (None for a revert)
+    pub previous: Option<PreviousTheme>,
+    /// Was this a rollback to a previous theme?
+    pub reverted: bool,
+}
+
+impl ApplyReport {
+    pub fn failed_hooks(&self) -> usize {
+        self.hooks.iter().filter(|h| !h.succeeded()).count()
+    }
+
+    /// Previous theme worth offering a revert to: only when a hook failed
+    pub fn revert_target(&self) -> Option<&PreviousTheme> {
+        if self.failed_hooks() == 0 {
+            return None;
+        }
+        self.previous.as_ref()
+    }
+}
+
+/// Apply a theme: swap the `current` symlink, write `.current-theme`, then run
+/// every executable in `hooks/theme-set.d` in parallel with `THEME_*` exported.
+///
+/// The previous `current` target and `.current-theme` are recorded in the
+/// report so a failed apply can be rolled back with [`revert`]. Hook output
+/// is captured under `log_dir` (see [`hook_log_dir`]).
+pub async fn apply_theme(
+    cruzalex_dir: &Path,
+    theme_name: &str,
+    hook_timeout: Duration,
+    log_dir: &Path,
+) -> Result<ApplyReport> {
+    // A name like "../x" would point `current` outside themes/
+    theme::check_name(theme_name)?;
+    let theme_dir = cruzalex_dir.join("themes").join(theme_name);
+    if !theme_dir.is_dir() {
+        bail!("Theme not found: {}", theme_name);
+    }
+
+    let previous = PreviousTheme::capture(cruzalex_dir);
+    let hooks = switch_to(cruzalex_dir, theme_name, &theme_dir, hook_timeout, log_dir).await?;
+
+    Ok(ApplyReport {
+        theme: theme_name.to_string(),
+        hooks,
+        previous,
+        reverted: false,
+    })
+}
+
+/// Restore a recorded previous theme and re-run the hooks against it
+pub async fn revert(
+    cruzalex_dir: &Path,
+    previous: &PreviousTheme,
+    hook_timeout: Duration,
+    log_dir: &Path,
+) -> Result<ApplyReport> {
+    if !previous.target.is_dir() {
+        bail!("Previous theme no longer exists: {}", previous.target.display());
+    }
+
+    let theme_name = previous.display_name();
+    let hooks = switch_to(cruzalex_dir, &theme_name, &previous.target, hook_timeout, log_dir).await?;
+
+    Ok(ApplyReport {
+        theme: theme_name,
+        hooks,
+        previous: None,
+        reverted: true,
+    })
+}
+
+/// Point `current` at `theme_dir`, record the name, and run all hooks
+async fn switch_to(
+    cruzalex_dir: &Path,
+    theme_name: &str,
+    theme_dir: &Path,
+    hook_timeout: Duration,
+    log_dir: &Path,
+) -> Result<Vec<HookResult>> {
+    let mut env = vec![
+        ("THEME_NAME".to_string(), theme_name.to_string()),
+        ("THEME_DIR".to_string(), theme_dir.display().to_string()),
+        ("CRUZALEX_DIR".to_string(), cruzalex_dir.display().to_string()),
+    ];
+    let colors_path = theme_dir.join("colors.toml");
+    let vars = match std::fs::read_to_string(&colors_path) {
+        Ok(content) => parse_color_vars(&content),
+        // Themes shipping only terminal configs still get THEME_* exported
+        Err(_) => palette::derive_palette(theme_dir)
+            .map(|(colors, _)| colors.color_vars())
+            .unwrap_or_default(),
+    };
+    env.extend(vars.into_iter().map(|(k, v)| (format!("THEME_{}", k), v)));
+
+    swap_current_link(cruzalex_dir, theme_dir)?;
+    std::fs::write(
+        cruzalex_dir.join(".current-theme"),
+        format!("{}\n", theme_name),
+    )
+    .context("Failed to write .current-theme")?;
+
+    let hooks = find_hooks(&cruzalex_dir.join("hooks/theme-set.d"));
+    Ok(run_hooks(hooks, env, log_dir, hook_timeout).await)
+}
+
+/// Point `current` at `target` atomically (symlink to a temp name, then rename)
+fn swap_current_link(cruzalex_dir: &Path, target: &Path) -> Result<()> {
+    let current = cruzalex_dir.join("current");
+    let tmp = cruzalex_dir.join(".current.new");
+    if tmp.symlink_metadata().is_ok() {
+        std::fs::remove_file(&tmp).context("Failed to remove stale .current.new")?;
+    }
+    std::os::unix::fs::symlink(target, &tmp).context("Failed to create current symlink")?;
+    std::fs::rename(&tmp, &current).context("Failed to swap current symlink")?;
+    Ok(())
+}
+
+/// `key = "value"` pairs from colors.toml, exported to hooks as `THEME_<key>`
+pub fn parse_color_vars(content: &str) -> Vec<(String, String)> {
+    parse_color_lines(content)
+        .into_iter()
+        .map(|(_, k, v)| (k, v))
+        .collect()
+}
+
+/// Executable regular files in the hooks directory, sorted by name
+pub fn find_hooks(hooks_dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(hooks_dir) else {
+        return Vec::new();
+    };
+    let mut hooks: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| {
+            std::fs::metadata(p)
+                .map(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+                .unwrap_or(false)
+        })
+        .collect();
+    hooks.sort();
+    hooks
+}
+
+/// Where hook stdout/stderr are captured
+///
+/// Output goes to files rather than pipes: hooks that background a daemon
+/// (waybar, swaybg) would otherwise hold the pipe open, and the daemon would
+/// die of SIGPIPE once we stop reading.
+pub fn hook_log_dir() -> PathBuf {
+    dirs::cache_dir()
+        .unwrap_or_else(|| PathBuf::from("/tmp"))
+        .join("cruzalex/hook-logs")
+}
+
+/// Run all hooks concurrently, each bounded by `hook_timeout`
+async fn run_hooks(
+    hooks: Vec<PathBuf>,
+    env: Vec<(String, String)>,
+    log_dir: &Path,
+    hook_timeout: Duration,
+) -> Vec<HookResult> {
+    std::fs::create_dir_all(log_dir).ok();
+
+    let mut set = JoinSet::new();
+    for hook in hooks {
+        let env = env.clone();
+        let log_dir = log_dir.to_path_buf();
+        set.spawn(async move { run_hook(hook, env, &log_dir, hook_timeout).await });
+    }
+
+    let mut results = Vec::new();
+    while let Some(res) = set.join_next().await {
+        if let Ok(result) = res {
+            results.push(result);
+        }
+    }
+    results.sort_by(|a, b| a.name.cmp(&b.name));
+    results
+}
+
+async fn run_hook(
+    hook: PathBuf,
+    env: Vec<(String, String)>,
+    log_dir: &Path,
+    hook_timeout: Duration,
+) -> HookResult {
+    let name = hook
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let stdout_path = log_dir.join(format!("{}.out", name));
+    let stderr_path = log_dir.join(format!("{}.err", name));
+    let start = Instant::now();
+
+    let child = match (
+        std::fs::File::create(&stdout_path),
+        std::fs::File::create(&stderr_path),
+    ) {
+        (Ok(out), Ok(err)) => Command::new(&hook)
+            .envs(env)
+            .stdin(Stdio::null())
+            .stdout(out)
+            .stderr(err)
+            .kill_on_drop(true)
+            .spawn(),
+        (Err(e), _) | (_, Err(e)) => Err(e),
+    };
+
+    let outcome = match child {
+        Err(e) => HookOutcome::SpawnError(e.to_string()),
+        Ok(mut child) => match tokio::time::timeout(hook_timeout, child.wait()).await {
+            Ok(Ok(status)) if status.success() => HookOutcome::Success,
+            Ok(Ok(status)) => HookOutcome::Failed(status.code()),
+            Ok(Err(e)) => HookOutcome::SpawnError(e.to_string()),
+            Err(_) => {
+                let _ = child.kill().await;
+                HookOutcome::TimedOut
+            }
+        },
+    };
+
+    HookResult {
+        name,
+        outcome,
+        duration: start.elapsed(),
+        stdout: read_tail(&stdout_path),
+        stderr: read_tail(&stderr_path),
+    }
+}
+
+/// Last `OUTPUT_TAIL_LINES` lines of a captured output file
+fn read_tail(path: &Path) -> String {
+    let Ok(bytes) = std::fs::read(path) else {
+        return String::new();
+    };
+    let text = String::from_utf8_lossy(&bytes);
+    let lines: Vec<&str> = text.trim_end().lines().collect();
+    let skip = lines.len().saturating_sub(OUTPUT_TAIL_LINES);
+    lines[skip..].join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A CRUZALEX_DIR with `themes/<name>/colors.toml` for each theme and an
+    /// empty hooks directory
+    fn cruzalex_dir(themes: &[&str]) -> tempfile::TempDir {
+        let tmp = tempfile::tempdir().unwrap();
+        for name in themes {
+            let theme = tmp.path().join("themes").join(name);
+            std::fs::create_dir_all(&theme).unwrap();
+            std::fs::write(
+                theme.join("colors.toml"),
+                "background = '#112233' # night\n\"foreground\" = \"#ddeeff\"\n",
+            )
+            .unwrap();
+        }
+        std::fs::create_dir_all(tmp.path().join("hooks/theme-set.d")).unwrap();
+        tmp
+    }
+
+    fn hook(dir: &Path, name: &str, script: &str) {
+        let path = dir.join("hooks/theme-set.d").join(name);
+        std::fs::write(&path, format!("#!/bin/sh\n{}\n", script)).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    #[test]
+    fn parses_quoted_and_commented_colors() {
+        let vars = parse_color_vars(
+            "# palette\n\
+             background = \"#1a1b26\" # dark\n\
+             'accent' = '#7aa2f7'\n\
+             opacity = 0.95  # bare\n\
+             [extra]\n\
+             bad-key = \"#000000\"\n\
+             empty = \"\"\n",
+        );
+        assert_eq!(
+            vars,
+            [
+                ("background".to_string(), "#1a1b26".to_string()),
+                ("accent".to_string(), "#7aa2f7".to_string()),
+                ("opacity".to_string(), "0.95".to_string()),
+            ]
+        );
+    }
+
+    /// Hooks see `THEME_<key>` with the key as written, like the shell script
+    #[tokio::test]
+    async fn applies_and_reports_each_hook() {
+        let dir = cruzalex_dir(&["night"]);
+        let dir = dir.path();
+        let logs = tempfile::tempdir().unwrap();
+        hook(
+            dir,
+            "10-env",
+            "echo \"$THEME_NAME $THEME_background $THEME_foreground\" > \"$CRUZALEX_DIR/env\"",
+        );
+        hook(dir, "20-fail", "exit 3");
+        hook(dir, "30-slow", "exec sleep 5");
+        std::fs::write(dir.join("hooks/theme-set.d/40-not-executable"), "exit 1").unwrap();
+        // Left behind by an interrupted apply
+        std::fs::write(dir.join(".current.new"), "").unwrap();
+
+        let report = apply_theme(dir, "night", Duration::from_millis(500), logs.path())
+            .await
+            .unwrap();
+        let outcomes: Vec<(&str, &HookOutcome)> = report
+            .hooks
+            .iter()
+            .map(|h| (h.name.as_str(), &h.outcome))
+            .collect();
+        assert_eq!(
+            outcomes,
+            [
+                ("10-env", &HookOutcome::Success),
+                ("20-fail", &HookOutcome::Failed(Some(3))),
+                ("30-slow", &HookOutcome::TimedOut),
+            ]
+        );
+        assert!(report.hooks[2].duration < Duration::from_secs(3));
+        let found: Vec<PathBuf> = find_hooks(&dir.join("hooks/theme-set.d"));
+        assert_eq!(found.len(), 3, "non-executable files are not hooks");
+        assert_eq!(report.failed_hooks(), 2);
+
+        assert_eq!(
+            std::fs::read_to_string(dir.join("env")).unwrap(),
+            "night #112233 #ddeeff\n"
+        );
+        assert_eq!(
+            std::fs::read_link(dir.join("current")).unwrap(),
+            dir.join("themes/night")
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.join(".current-theme")).unwrap(),
+            "night\n"
+        );
+        assert!(dir.join(".current.new").symlink_metadata().is_err());
+    }
+
+    #[tokio::test]
+    async fn keeps_the_tail_of_hook_output() {
+        let dir = cruzalex_dir(&[]);
+        let dir = dir.path();
+        let logs = tempfile::tempdir().unwrap();
+        hook(
+            dir,
+            "10-chatty",
+            "for i in $(seq 1 15); do echo out$i; echo err$i >&2; done\nexit 4",
+        );
+        hook(dir, "20-exact", "seq 1 10");
+        hook(dir, "30-quiet", "true");
+
+        let hooks = find_hooks(&dir.join("hooks/theme-set.d"));
+        let results = run_hooks(hooks, Vec::new(), logs.path(), DEFAULT_HOOK_TIMEOUT).await;
+
+        let lines = |prefix: &str, range: std::ops::RangeInclusive<u32>| {
+            range.map(|i| format!("{}{}", prefix, i)).collect::<Vec<_>>().join("\n")
+        };
+        let chatty = &results[0];
+        assert_eq!(chatty.outcome, HookOutcome::Failed(Some(4)));
+        assert_eq!(chatty.stdout, lines("out", 6..=15));
+        assert_eq!(chatty.stderr, lines("err", 6..=15));
+        assert_eq!(results[1].stdout, lines("", 1..=10));
+        assert_eq!(results[2].stdout, "");
+
+        // The log files keep everything
+        let full = std::fs::read_to_string(logs.path().join("10-chatty.out")).unwrap();
+        assert_eq!(full, lines("out", 1..=15) + "\n");
+    }
+
+    #[tokio::test]
+    async fn reverts_to_the_previous_theme() {
+        let dir = cruzalex_dir(&["day", "night"]);
+        let dir = dir.path();
+        let logs = tempfile::tempdir().unwrap();
+        hook(
+            dir,
+            "10-record",
+            "echo \"$THEME_NAME\" >> \"$CRUZALEX_DIR/applied\"\n[ \"$THEME_NAME\" != night ]",
+        );
+
+        let first = apply_theme(dir, "day", DEFAULT_HOOK_TIMEOUT, logs.path()).await.unwrap();
+        assert!(first.revert_target().is_none());
+
+        let failed = apply_theme(dir, "night", DEFAULT_HOOK_TIMEOUT, logs.path()).await.unwrap();
+        assert_eq!(failed.failed_hooks(), 1);
+        let previous = failed.revert_target().expect("failed apply offers a revert");
+        assert_eq!(previous.display_name(), "day");
+
+        let reverted = revert(dir, previous, DEFAULT_HOOK_TIMEOUT, logs.path()).await.unwrap();
+        assert!(reverted.reverted);
+        assert_eq!(reverted.failed_hooks(), 0);
+        assert_eq!(
+            std::fs::read_link(dir.join("current")).unwrap(),
+            dir.join("themes/day")
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.join(".current-theme")).unwrap(),
+            "day\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.join("applied")).unwrap(),
+            "day\nnight\nday\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_names_outside_themes() {
+        let dir = cruzalex_dir(&["night"]);
+        let dir = dir.path();
+        std::fs::create_dir_all(dir.join("elsewhere")).unwrap();
+        for name in ["../elsewhere", "night/..", ".hidden", "current"] {
+            let err = apply_theme(dir, name, DEFAULT_HOOK_TIMEOUT, dir)
+                .await
+                .unwrap_err();
+            assert!(err.to_string().contains("invalid theme name"), "{}: {}", name, err);
+        }
+        assert!(dir.join("current").symlink_metadata().is_err());
+    }
+}
@@ -0,0 +1,267 @@
+//! Live theme list from the awesome-omarchy README
+//!
+//! The README lists themes as markdown bullets or table rows under a
+//! "Themes" heading, each linking to its GitHub repo and often to the
+//! author. Everything outside those sections (tools, dotfiles, guides) is
+//! ignored.
+
+use crate::theme::{normalize_repo_url, Theme};
+use std::collections::HashSet;
+
+/// Raw upstream README
+pub const README_URL: &str =
+    "https://raw.githubusercontent.com/aorumbayev/awesome-omarchy/main/README.md";
+
+/// Themes listed in the README's theme sections, in order, without duplicates
+pub fn parse_readme(markdown: &str) -> Vec<Theme> {
+    let mut section: Option<usize> = None;
+    let mut seen = HashSet::new();
+    let mut themes = Vec::new();
+
+    for line in markdown.lines() {
+        let line = line.trim();
+        if let Some(level) = heading_level(line) {
+            let title = line[level..].trim().to_lowercase();
+            // Subheadings ("### Light themes") stay inside the section
+            match section {
+                Some(outer) if level > outer => {}
+                _ => section = title.contains("theme").then_some(level),
+            }
+            continue;
+        }
+        if section.is_none()
+            || !(line.starts_with("- ") || line.starts_with("* ") || line.starts_with('|'))
+        {
+            continue;
+        }
+        let Some(theme) = parse_entry(line) else {
+            continue;
+        };
+        if seen.insert(normalize_repo_url(
+            theme.remote_url.as_deref().unwrap_or_default(),
+        )) {
+            themes.push(theme);
+        }
+    }
+    themes
+}
+
+fn heading_level(line: &str) -> Option<usize> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    (level > 0 && line[level..].starts_with(' ')).then_some(level)
+}
+
+/// A theme from one bullet or table row: the first repo link names it, an
+/// `@user` or profile link credits the author (else the repo owner)
+fn parse_entry(line: &str) -> Option<Theme> {
+    let links = links(line);
+    let (text, owner, repo) = links.iter().find_map(|(text, url)| {
+        let (owner, repo) = repo_of(url)?;
+        Some((text, owner, repo))
+    })?;
+
+    let name = match slug(text) {
+        Some(name) if !text.starts_with('!') => name,
+        _ => slug(repo)?,
+    };
+    let mention = line
+        .split_whitespace()
+        .find_map(|word| word.strip_prefix('@'))
+        .map(|user| user.trim_end_matches(|c: char| !c.is_alphanumeric()));
+    let author = links
+        .iter()
+        .find_map(|(text, url)| profile_of(url).or_else(|| text.strip_prefix('@')))
+        .or(mention)
+        .unwrap_or(owner);
+    Some(Theme::from_remote(
+        &name,
+        &format!("https://github.com/{}/{}", owner, repo),
+        Some(author),
+    ))
+}
+
+/// `(text, url)` of every `[text](url)`, including image links (`![..](..)`
+/// text) and links wrapping an image
+fn links(line: &str) -> Vec<(&str, &str)> {
+    let mut links = Vec::new();
+    let mut rest = line;
+    let mut offset = 0;
+    while let Some(mid) = rest.find("](") {
+        let mid = offset + mid;
+        let url_start = mid + 2;
+        let Some(url_len) = line[url_start..].find(')') else {
+            break;
+        };
+        // Walk back to the '[' that opens this text, skipping nested brackets
+        let mut depth = 0;
+        let open = line[..mid].char_indices().rev().find_map(|(i, c)| match c {
+            ']' => {
+                depth += 1;
+                None
+            }
+            '[' if depth == 0 => Some(i),
+            '[' => {
+                depth -= 1;
+                None
+            }
+            _ => None,
+        });
+        if let Some(open) = open {
+            let open = if line[..open].ends_with('!') {
+                open - 1
+            } else {
+                open
+            };
+            let text = &line[open..mid];
+            let text = text.strip_prefix('[').unwrap_or(text);
+            links.push((text.trim(), line[url_start..url_start + url_len].trim()));
+        }
+        offset = url_start;
+        rest = &line[offset..];
+    }
+    links
+}
+
+/// `(owner, repo)` of a `https://github.com/owner/repo` link
+fn repo_of(url: &str) -> Option<(&str, &str)> {
+    let path = url
+        .strip_prefix("https://github.com/")?
+        .trim_end_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    let (owner, repo) = path.split_once('/')?;
+    (!owner.is_empty() && !repo.is_empty() && !repo.contains(['/', '#', '?']))
+        .then_some((owner, repo))
+}
+
+/// `user` of a `https://github.com/user` profile link
+fn profile_of(url: &str) -> Option<&str> {
+    let user = url
+        .strip_prefix("https://github.com/")?
+        .trim_end_matches('/');
+    (!user.is_empty() && !user.contains(['/', '#', '?'])).then_some(user)
+}
+
+/// "Omarchy Ayu Mirage Theme" -> "ayu-mirage", as the embedded list names them
+fn slug(text: &str) -> Option<String> {
+    let mut slug = String::new();
+    for c in text.chars().flat_map(char::to_lowercase).filter_map(fold_accent) {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+    let slug = slug.strip_prefix("omarchy-").unwrap_or(slug);
+    let slug = slug.strip_suffix("-theme").unwrap_or(slug);
+    (!slug.is_empty() && slug != "omarchy").then(|| slug.to_string())
+}
+
+/// Latin letters without their accents (é -> e). Other non-ASCII letters
+/// are dropped, since the embedded names are plain ASCII.
+fn fold_accent(c: char) -> Option<char> {
+    if c.is_ascii() {
+        return Some(c);
+    }
+    let base = match c {
+        'à'..='å' | 'ā' | 'ă' | 'ą' => 'a',
+        'ç' | 'ć' | 'č' => 'c',
+        'ď' | 'đ' => 'd',
+        'è'..='ë' | 'ē' | 'ė' | 'ę' | 'ě' => 'e',
+        'ì'..='ï' | 'ī' | 'į' | 'ı' => 'i',
+        'ł' => 'l',
+        'ñ' | 'ń' | 'ň' => 'n',
+        'ò'..='ö' | 'ø' | 'ō' | 'ő' => 'o',
+        'ř' => 'r',
+        'ś' | 'š' | 'ş' => 's',
+        'ť' | 'ţ' => 't',
+        'ù'..='ü' | 'ū' | 'ů' | 'ű' => 'u',
+        'ý' | 'ÿ' => 'y',
+        'ź' | 'ż' | 'ž' => 'z',
+        _ if c.is_alphanumeric() => return None,
+        // Non-ASCII punctuation still separates words
+        _ => ' ',
+    };
+    Some(base)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const README: &str = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/testdata/awesome-omarchy/README.md"
+    ));
+
+    fn find<'a>(themes: &'a [Theme], name: &str) -> &'a Theme {
+        themes
+            .iter()
+            .find(|t| t.name == name)
+            .unwrap_or_else(|| panic!("{} not parsed", name))
+    }
+
+    #[test]
+    fn parses_theme_lists_and_tables() {
+        let themes = parse_readme(README);
+        let names: Vec<&str> = themes.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "aetheria",
+                "ash",
+                "ayu-mirage",
+                "dracula-official",
+                "kanso",
+                "nord",
+                "rose-pine-dawn",
+                "tokyo-night-storm",
+                "f1",
+                "synthwave84",
+            ]
+        );
+
+        let mirage = find(&themes, "ayu-mirage");
+        assert_eq!(
+            mirage.remote_url.as_deref(),
+            Some("https://github.com/fdidron/omarchy-ayumirage")
+        );
+        assert_eq!(mirage.author.as_deref(), Some("fdidron"));
+        // Credited author, not the repo owner
+        assert_eq!(
+            find(&themes, "dracula-official").author.as_deref(),
+            Some("zenorocha")
+        );
+        assert_eq!(find(&themes, "kanso").author.as_deref(), Some("someguy"));
+        // Named from the repo when the link wraps a screenshot
+        assert_eq!(
+            find(&themes, "synthwave84").remote_url.as_deref(),
+            Some("https://github.com/HANCORE-linux/omarchy-synthwave84-theme")
+        );
+    }
+
+    #[test]
+    fn skips_everything_outside_theme_sections() {
+        let themes = parse_readme(README);
+        assert!(themes
+            .iter()
+            .all(|t| t.name != "omarchy-hyprland-tools" && t.name != "omarchy"));
+        assert!(!themes
+            .iter()
+            .any(|t| t.remote_url.as_deref().unwrap().contains("/tree/")));
+        assert!(
+            parse_readme("# Tools\n- [Walker](https://github.com/abenz1267/walker)\n").is_empty()
+        );
+    }
+
+    #[test]
+    fn slugs_match_the_embedded_names() {
+        assert_eq!(
+            slug("Omarchy Ayu Mirage Theme").as_deref(),
+            Some("ayu-mirage")
+        );
+        assert_eq!(slug("Rosé Pine (Dawn)").as_deref(), Some("rose-pine-dawn"));
+        assert_eq!(slug("Kanagawa 波 — Dragon").as_deref(), Some("kanagawa-dragon"));
+        assert_eq!(slug("Omarchy"), None);
+    }
+}
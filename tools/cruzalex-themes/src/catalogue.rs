@@ -0,0 +1,259 @@
+//! On-disk cache of the remote theme catalogue
+//!
+//! The merged curated + topic list, with stars, descriptions and resolved
+//! preview URLs, is kept in `~/.cache/cruzalex/catalogue.json` so startup
+//! doesn't wait on the network and `--offline` still shows remote themes.
+
+use crate::github::RepoMeta;
+use crate::theme::{ColorPalette, Theme};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// One remote theme as cached
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CatalogueEntry {
+    pub name: String,
+    pub remote_url: String,
+    pub author: Option<String>,
+    pub stars: Option<u32>,
+    pub description: Option<String>,
+    /// Preview URL that last served an image; None once every candidate failed
+    pub preview_url: Option<String>,
+    /// ETag of the GitHub repo response `stars` came from
+    #[serde(default)]
+    pub etag: Option<String>,
+    #[serde(default)]
+    pub default_branch: Option<String>,
+    #[serde(default)]
+    pub pushed_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub topics: Vec<String>,
+    #[serde(default)]
+    pub license: Option<String>,
+    /// The repo's colors.toml, once fetched, so filters and the contrast
+    /// audit cover themes that were never selected
+    #[serde(default)]
+    pub colors: Option<ColorPalette>,
+}
+
+impl CatalogueEntry {
+    fn from_theme(theme: &Theme) -> Option<Self> {
+        Some(Self {
+            name: theme.name.clone(),
+            remote_url: theme.remote_url.clone()?,
+            author: theme.author.clone(),
+            stars: theme.stars,
+            description: theme.description.clone(),
+            preview_url: theme.preview_url.clone(),
+            etag: None,
+            default_branch: theme.default_branch.clone(),
+            pushed_at: theme.pushed_at,
+            topics: theme.topics.clone(),
+            license: theme.license.clone(),
+            colors: theme.colors.clone(),
+        })
+    }
+
+    fn to_theme(&self) -> Theme {
+        let mut theme = Theme::from_remote(&self.name, &self.remote_url, self.author.as_deref());
+        theme.stars = self.stars;
+        theme.description = self.description.clone();
+        theme.preview_url = self.preview_url.clone();
+        theme.default_branch = self.default_branch.clone();
+        theme.pushed_at = self.pushed_at;
+        theme.topics = self.topics.clone();
+        theme.license = self.license.clone();
+        if let Some(colors) = &self.colors {
+            theme.set_remote_palette(colors.clone());
+        }
+        theme
+    }
+}
+
+/// The cached catalogue and when it was fetched
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Catalogue {
+    /// Unix seconds of the last successful fetch
+    pub fetched_at: u64,
+    pub entries: Vec<CatalogueEntry>,
+}
+
+/// `~/.cache/cruzalex/catalogue.json`
+pub fn catalogue_path() -> PathBuf {
+    dirs::cache_dir()
+        .unwrap_or_else(|| PathBuf::from("/tmp"))
+        .join("cruzalex/catalogue.json")
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+impl Catalogue {
+    /// Freshly fetched remote themes, keeping what the cache learned about
+    /// them that a bare listing doesn't carry (stars and repo metadata,
+    /// resolved previews)
+    pub fn from_fetch(themes: &mut [Theme], previous: &Catalogue) -> Self {
+        let known: HashMap<&str, &CatalogueEntry> =
+            previous.entries.iter().map(|e| (e.name.as_str(), e)).collect();
+        for theme in themes.iter_mut() {
+            let Some(old) = known.get(theme.name.as_str()) else {
+                continue;
+            };
+            theme.stars = theme.stars.or(old.stars);
+            theme.description = theme.description.take().or_else(|| old.description.clone());
+            theme.default_branch = theme.default_branch.take().or_else(|| old.default_branch.clone());
+            theme.pushed_at = theme.pushed_at.or(old.pushed_at);
+            if theme.topics.is_empty() {
+                theme.topics = old.topics.clone();
+            }
+            theme.license = theme.license.take().or_else(|| old.license.clone());
+            if old.preview_url.is_some() {
+                theme.preview_url = old.preview_url.clone();
+            }
+            if let (None, Some(colors)) = (&theme.colors, &old.colors) {
+                theme.set_remote_palette(colors.clone());
+            }
+        }
+        let mut entries: Vec<CatalogueEntry> =
+            themes.iter().filter_map(CatalogueEntry::from_theme).collect();
+        for entry in &mut entries {
+            entry.etag = known.get(entry.name.as_str()).and_then(|old| old.etag.clone());
+        }
+        Self { fetched_at: now(), entries }
+    }
+
+    /// None if the file is missing or unreadable; a bad cache is just a cold start
+    pub fn load(path: &Path) -> Option<Self> {
+        let content = std::fs::read_to_string(path).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// Write atomically so an interrupted save can't leave half a file
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_string(self)?)
+            .with_context(|| format!("writing {}", tmp.display()))?;
+        std::fs::rename(&tmp, path).with_context(|| format!("writing {}", path.display()))?;
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn themes(&self) -> Vec<Theme> {
+        self.entries.iter().map(CatalogueEntry::to_theme).collect()
+    }
+
+    pub fn age(&self) -> Duration {
+        Duration::from_secs(now().saturating_sub(self.fetched_at))
+    }
+
+    pub fn set_stars(&mut self, stars: &HashMap<String, u32>, etags: &HashMap<String, String>) {
+        for entry in &mut self.entries {
+            if let Some(&s) = stars.get(&entry.name) {
+                entry.stars = Some(s);
+                entry.etag = etags.get(&entry.name).cloned();
+            }
+        }
+    }
+
+    pub fn set_repo_meta(&mut self, metadata: &HashMap<String, RepoMeta>) {
+        for entry in &mut self.entries {
+            if let Some(meta) = metadata.get(&entry.name) {
+                entry.stars = Some(meta.stars);
+                entry.description = meta.description.clone();
+                entry.default_branch = meta.default_branch.clone();
+                entry.pushed_at = meta.pushed_at;
+                entry.topics = meta.topics.clone();
+                entry.license = meta.license.clone();
+            }
+        }
+    }
+
+    /// ETag to revalidate `name`'s stars with; only useful while stars are cached
+    pub fn etag(&self, name: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|e| e.name == name && e.stars.is_some())
+            .and_then(|e| e.etag.as_deref())
+    }
+
+    pub fn set_colors(&mut self, name: &str, colors: &ColorPalette) {
+        if let Some(entry) = self.entries.iter_mut().find(|e| e.name == name) {
+            entry.colors = Some(colors.clone());
+        }
+    }
+
+    pub fn set_preview_url(&mut self, name: &str, url: Option<String>) {
+        if let Some(entry) = self.entries.iter_mut().find(|e| e.name == name) {
+            entry.preview_url = url;
+        }
+    }
+}
+
+/// "just now", "5m ago", "3h ago", "2d ago"
+pub fn format_age(age: Duration) -> String {
+    let secs = age.as_secs();
+    match secs {
+        0..=59 => "just now".to_string(),
+        60..=3599 => format!("{}m ago", secs / 60),
+        3600..=86399 => format!("{}h ago", secs / 3600),
+        _ => format!("{}d ago", secs / 86400),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn refetch_keeps_cached_stars_and_resolved_preview() {
+        let mut cached = Theme::from_remote("nord", "https://github.com/a/omarchy-nord-theme", Some("a"));
+        cached.stars = Some(42);
+        cached.preview_url = Some("https://raw.githubusercontent.com/a/omarchy-nord-theme/main/theme.png".into());
+        let previous = Catalogue::from_fetch(&mut [cached], &Catalogue::default());
+
+        let mut fresh = [Theme::from_remote("nord", "https://github.com/a/omarchy-nord-theme", Some("a"))];
+        let catalogue = Catalogue::from_fetch(&mut fresh, &previous);
+        assert_eq!(fresh[0].stars, Some(42));
+        assert!(fresh[0].preview_url.as_deref().unwrap().ends_with("/main/theme.png"));
+
+        let mut catalogue = catalogue;
+        let colors: ColorPalette = toml::from_str("background = \"#2e3440\"\nforeground = \"#d8dee9\"").unwrap();
+        catalogue.set_colors("nord", &colors);
+
+        let restored = catalogue.themes();
+        assert_eq!(restored[0].name, "nord");
+        assert_eq!(restored[0].stars, Some(42));
+        // Cached palettes come back audited
+        assert!(restored[0].contrast.is_some());
+        let mut refetched = [Theme::from_remote("nord", "https://github.com/a/omarchy-nord-theme", None)];
+        Catalogue::from_fetch(&mut refetched, &catalogue);
+        assert_eq!(refetched[0].colors.as_ref().unwrap().background, colors.background);
+    }
+
+    #[test]
+    fn round_trips_through_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("catalogue.json");
+        let mut themes = [Theme::from_remote("kanso", "https://github.com/b/kanso", None)];
+        let catalogue = Catalogue::from_fetch(&mut themes, &Catalogue::default());
+        catalogue.save(&path).unwrap();
+        let loaded = Catalogue::load(&path).unwrap();
+        assert_eq!(loaded.entries.len(), 1);
+        assert_eq!(loaded.fetched_at, catalogue.fetched_at);
+        assert_eq!(format_age(Duration::from_secs(7200)), "2h ago");
+    }
+}
@@ -0,0 +1,422 @@
+//! Headless subcommands (no TUI, no raw mode)
+
+use crate::app::{cruzalex_dir, App, FilterMode, SortMode};
+use crate::apply;
+use crate::catalogue::format_age;
+use crate::diagnostics::{self, ParseMode, Severity};
+use crate::generate::{self, Target};
+use crate::install;
+use crate::plan;
+use crate::theme::{self, Theme};
+use crate::update;
+use anyhow::{bail, Context, Result};
+use chrono::Utc;
+use serde::Serialize;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Output format for `list`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    Json,
+    Tsv,
+    Plain,
+}
+
+/// One catalogue row, as printed by `list`
+#[derive(Debug, Serialize)]
+struct ThemeEntry<'a> {
+    name: &'a str,
+    display_name: &'a str,
+    status: String,
+    favorite: bool,
+    light: Option<bool>,
+    backgrounds: usize,
+    author: Option<&'a str>,
+    stars: Option<u32>,
+    remote_url: Option<&'a str>,
+    local_path: Option<String>,
+    description: Option<&'a str>,
+    pushed_at: Option<String>,
+    topics: &'a [String],
+    license: Option<&'a str>,
+}
+
+impl<'a> ThemeEntry<'a> {
+    fn new(theme: &'a Theme, favorite: bool) -> Self {
+        Self {
+            name: &theme.name,
+            display_name: &theme.display_name,
+            status: theme.status.label().to_lowercase(),
+            favorite,
+            light: theme.is_light,
+            backgrounds: theme.background_count,
+            author: theme.author.as_deref(),
+            stars: theme.stars,
+            remote_url: theme.remote_url.as_deref(),
+            local_path: theme.local_path.as_ref().map(|p| p.display().to_string()),
+            description: theme.description.as_deref(),
+            pushed_at: theme.pushed_at.map(|t| t.to_rfc3339()),
+            topics: &theme.topics,
+            license: theme.license.as_deref(),
+        }
+    }
+}
+
+/// Build the merged catalogue and print it
+///
+/// Remote sources are skipped with `local_only` or when the filter can only
+/// ever match installed themes. Stars and repo metadata are fetched only when
+/// sorting by them.
+/// `offline` lists remote themes from the cached catalogue instead.
+pub async fn list(
+    format: OutputFormat,
+    filter: FilterMode,
+    sort: SortMode,
+    local_only: bool,
+    offline: bool,
+) -> Result<()> {
+    let mut app = App::new().await?;
+    app.offline = offline;
+
+    if !local_only && filter != FilterMode::Installed {
+        app.refresh_remote_themes().await?;
+        if matches!(sort, SortMode::Stars | SortMode::Updated) {
+            app.fetch_stars_now().await;
+            if let Some(limit) = app.rate_limit {
+                eprintln!(
+                    "warning: GitHub rate limited until {}, some stars are cached or missing",
+                    limit.reset_time()
+                );
+            }
+        }
+    }
+
+    app.filter_mode = filter;
+    app.sort_mode = sort;
+    app.update_filter();
+
+    let entries: Vec<ThemeEntry> = app
+        .filtered_themes
+        .iter()
+        .map(|&idx| {
+            let theme = &app.themes[idx];
+            ThemeEntry::new(theme, app.is_favorite(&theme.name))
+        })
+        .collect();
+
+    match format {
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&entries)?);
+        }
+        OutputFormat::Tsv => {
+            println!("name\tdisplay_name\tstatus\tfavorite\tlight\tbackgrounds\tauthor\tstars\tremote_url\tlocal_path");
+            for e in &entries {
+                println!(
+                    "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+                    tsv_field(e.name),
+                    tsv_field(e.display_name),
+                    e.status,
+                    e.favorite,
+                    e.light.map(|l| l.to_string()).unwrap_or_default(),
+                    e.backgrounds,
+                    tsv_field(e.author.unwrap_or("")),
+                    e.stars.map(|s| s.to_string()).unwrap_or_default(),
+                    tsv_field(e.remote_url.unwrap_or("")),
+                    tsv_field(e.local_path.as_deref().unwrap_or("")),
+                );
+            }
+        }
+        OutputFormat::Plain => {
+            for &idx in &app.filtered_themes {
+                let theme = &app.themes[idx];
+                let fav = if app.is_favorite(&theme.name) { "★" } else { " " };
+                let stars = theme.stars.map(|s| format!("  ⭐{}", s)).unwrap_or_default();
+                println!("{} {} {}{}", fav, theme.status.symbol(), theme.name, stars);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Escape a TSV field so a tab or newline in it can't shift columns or rows
+fn tsv_field(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\t' => out.push_str("\\t"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Apply a theme natively and report each hook
+///
+/// With `rollback`, a failed hook restores the previous theme automatically.
+pub async fn apply(name: &str, hook_timeout: Duration, rollback: bool) -> Result<()> {
+    let dir = cruzalex_dir()?;
+    let log_dir = apply::hook_log_dir();
+    let report = apply::apply_theme(&dir, name, hook_timeout, &log_dir).await?;
+
+    println!("Applied theme: {}", report.theme);
+    print_hooks(&report);
+
+    let failed = report.failed_hooks();
+    if failed == 0 {
+        return Ok(());
+    }
+
+    match report.revert_target() {
+        Some(previous) if rollback => {
+            let reverted = apply::revert(&dir, previous, hook_timeout, &log_dir).await?;
+            println!("Reverted to: {}", reverted.theme);
+            print_hooks(&reverted);
+        }
+        Some(previous) => {
+            println!(
+                "Previous theme was '{}'; re-run with --rollback to revert automatically",
+                previous.display_name()
+            );
+        }
+        None => {}
+    }
+
+    bail!("{} of {} hooks failed", failed, report.hooks.len());
+}
+
+fn print_hooks(report: &apply::ApplyReport) {
+    for hook in &report.hooks {
+        let mark = if hook.succeeded() { "✓" } else { "✗" };
+        println!(
+            "  {} {:<24} {:<12} {:.2}s",
+            mark,
+            hook.name,
+            hook.outcome.label(),
+            hook.duration.as_secs_f64()
+        );
+        if !hook.succeeded() {
+            for line in hook.stderr.lines().chain(hook.stdout.lines()) {
+                println!("      {}", line);
+            }
+        }
+    }
+}
+
+/// Install a theme from a directory, archive or archive URL
+pub async fn install(from: &str, name: Option<&str>, offline: bool) -> Result<()> {
+    if offline && install::is_url(from) {
+        bail!("Offline: can't download {}", from);
+    }
+    let themes_dir = cruzalex_dir()?.join("themes");
+    let dest = install::install_from(from, &themes_dir, name).await?;
+    let theme = Theme::from_local(dest.clone(), None)?;
+    println!("Installed '{}' to {}", theme.name, dest.display());
+    if !theme.diagnostics.is_empty() {
+        println!(
+            "  colors.toml has {} issue(s); see `cruzalex-themes check {}`",
+            theme.diagnostics.len(),
+            theme.name
+        );
+    }
+    Ok(())
+}
+
+/// Print the dry-run plan for applying a theme
+pub fn plan(name: &str) -> Result<()> {
+    let plan = plan::plan_apply(&cruzalex_dir()?, name)?;
+
+    println!("Dry run: applying '{}' would", plan.theme);
+    for action in &plan.core {
+        println!("  {}", action.describe());
+    }
+    println!();
+    println!("Hooks ({}):", plan.hooks.len());
+    for hook in &plan.hooks {
+        if !hook.known {
+            println!("  {}  (custom hook, effects unknown)", hook.name);
+            continue;
+        }
+        println!("  {}", hook.name);
+        if hook.actions.is_empty() {
+            println!("      nothing for this theme");
+        }
+        for action in &hook.actions {
+            println!("      {}", action.describe());
+        }
+    }
+
+    let disruptive = plan.disruptive_count();
+    if disruptive > 0 {
+        println!();
+        println!("{} running process(es) would be signalled or restarted", disruptive);
+    }
+    Ok(())
+}
+
+/// Write generated app configs for a theme's palette
+pub fn generate(name: &str, out: Option<PathBuf>, only: &[Target]) -> Result<()> {
+    theme::check_name(name)?;
+    let theme_dir = cruzalex_dir()?.join("themes").join(name);
+    if !theme_dir.is_dir() {
+        bail!("Theme not found: {}", name);
+    }
+    let theme = Theme::from_local(theme_dir, None)?;
+    let palette = theme
+        .colors
+        .with_context(|| format!("Theme '{}' has no usable color palette", name))?;
+
+    let out = match out {
+        Some(dir) => dir,
+        None => dirs::config_dir().context("Could not find config directory")?,
+    };
+    let targets = if only.is_empty() { &Target::ALL[..] } else { only };
+
+    let written = generate::write_targets(targets, &palette, name, &out)?;
+    for target in targets {
+        let path = out.join(target.relative_path());
+        let state = if written.contains(&path) { "wrote  " } else { "skipped" };
+        println!("  {} {:<9} {}", state, target.label(), path.display());
+    }
+    Ok(())
+}
+
+/// Fetch one theme (or, without a name, every git theme), print the
+/// incoming commits and fast-forward unless `dry_run`
+pub fn update(name: Option<&str>, dry_run: bool, offline: bool) -> Result<()> {
+    if offline {
+        bail!("Offline: can't fetch theme updates");
+    }
+    let themes_dir = cruzalex_dir()?.join("themes");
+    let names: Vec<String> = match name {
+        Some(name) => {
+            theme::check_name(name)?;
+            vec![name.to_string()]
+        }
+        None => {
+            let mut names: Vec<String> = std::fs::read_dir(&themes_dir)
+                .with_context(|| format!("Failed to read {}", themes_dir.display()))?
+                .filter_map(|e| e.ok())
+                .filter(|e| update::is_git_theme(&e.path()))
+                .map(|e| e.file_name().to_string_lossy().to_string())
+                .collect();
+            names.sort();
+            names
+        }
+    };
+
+    let mut failed = 0;
+    for name in &names {
+        let path = themes_dir.join(name);
+        if !update::is_git_theme(&path) {
+            println!("✗ {}: not a git clone; reinstall it to update", name);
+            failed += 1;
+            continue;
+        }
+        let incoming = match update::fetch(&path) {
+            Ok(incoming) => incoming,
+            Err(e) => {
+                println!("✗ {}: {:#}", name, e);
+                failed += 1;
+                continue;
+            }
+        };
+        if incoming.commits.is_empty() {
+            println!("✓ {} is up to date", name);
+            continue;
+        }
+
+        println!("{}: {} new commit(s) on {}", name, incoming.commits.len(), incoming.upstream);
+        for commit in &incoming.commits {
+            let age = commit
+                .time
+                .map(|t| format_age((Utc::now() - t).to_std().unwrap_or_default()))
+                .unwrap_or_default();
+            println!("    {} {} ({}, {})", commit.id, commit.summary, commit.author, age);
+        }
+        if !incoming.fast_forward {
+            println!("  ✗ local commits; not a fast-forward, update it with git");
+            failed += 1;
+            continue;
+        }
+        if dry_run {
+            continue;
+        }
+        match update::fast_forward(&path, incoming.target) {
+            Ok(()) => println!("  ✓ updated"),
+            Err(e) => {
+                println!("  ✗ {:#}", e);
+                failed += 1;
+            }
+        }
+    }
+
+    if failed > 0 {
+        bail!("{} theme(s) could not be updated", failed);
+    }
+    Ok(())
+}
+
+/// Print colors.toml diagnostics; fails if any theme has errors
+pub fn check(name: Option<&str>, strict: bool) -> Result<()> {
+    let themes_dir = cruzalex_dir()?.join("themes");
+    let names: Vec<String> = match name {
+        Some(name) => {
+            theme::check_name(name)?;
+            vec![name.to_string()]
+        }
+        None => {
+            let mut names: Vec<String> = std::fs::read_dir(&themes_dir)
+                .with_context(|| format!("Failed to read {}", themes_dir.display()))?
+                .filter_map(|e| e.ok())
+                .filter(|e| e.path().join("colors.toml").is_file())
+                .map(|e| e.file_name().to_string_lossy().to_string())
+                .collect();
+            names.sort();
+            names
+        }
+    };
+    let mode = if strict { ParseMode::Strict } else { ParseMode::Lenient };
+
+    let mut errors = 0;
+    for name in &names {
+        let path = themes_dir.join(name).join("colors.toml");
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let (_, diags) = diagnostics::load_colors(&content, mode);
+        if diags.is_empty() {
+            println!("✓ {}", name);
+            continue;
+        }
+        println!("⚠ {}", name);
+        for diag in &diags {
+            let level = match diag.severity {
+                Severity::Error => "error",
+                Severity::Warning => "warning",
+            };
+            println!("    {}: {}", level, diag.describe());
+        }
+        errors += diags.iter().filter(|d| d.severity == Severity::Error).count();
+    }
+
+    if errors > 0 {
+        bail!("{} error(s) in colors.toml", errors);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tsv_fields_stay_on_one_line() {
+        assert_eq!(tsv_field("Tokyo Night"), "Tokyo Night");
+        assert_eq!(tsv_field("a\tb\nc\r"), "a\\tb\\nc\\r");
+        assert_eq!(tsv_field("C:\\themes"), "C:\\\\themes");
+    }
+}
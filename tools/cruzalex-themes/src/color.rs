@@ -0,0 +1,358 @@
+//! RGB(A) color values shared by the palette loaders, generators and UI
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+/// An sRGB color with 8-bit alpha (255 = opaque)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rgba {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+/// Why a color string was rejected
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("invalid color `{input}`: {reason}")]
+pub struct ColorError {
+    pub input: String,
+    pub reason: &'static str,
+}
+
+impl Rgba {
+    pub const BLACK: Rgba = Rgba::hex(0x000000);
+    pub const WHITE: Rgba = Rgba::hex(0xffffff);
+
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b, a: 255 }
+    }
+
+    /// Opaque color from `0xRRGGBB`
+    pub const fn hex(rgb: u32) -> Self {
+        Self::rgb((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8)
+    }
+
+    /// Parse `#rgb`, `#rrggbb`, `#rrggbbaa`, `0xRRGGBB`, `rgb(r, g, b)`,
+    /// `rgba(r, g, b, a)` or Hyprland's `rgb(rrggbb)` / `rgba(rrggbbaa)`
+    pub fn parse(input: &str) -> Result<Self, ColorError> {
+        let s = input.trim();
+        let err = |reason| ColorError {
+            input: input.to_string(),
+            reason,
+        };
+
+        if let Some(hex) = s.strip_prefix('#') {
+            return parse_hex_digits(hex).ok_or_else(|| err("expected 3, 6 or 8 hex digits after `#`"));
+        }
+        if let Some(hex) = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+            return match hex.len() {
+                6 => parse_hex_digits(hex),
+                _ => None,
+            }
+            .ok_or_else(|| err("expected 6 hex digits after `0x`"));
+        }
+        if let Some(args) = s
+            .strip_prefix("rgba(")
+            .or_else(|| s.strip_prefix("rgb("))
+            .and_then(|rest| rest.strip_suffix(')'))
+        {
+            return parse_function_args(args).ok_or_else(|| err("malformed rgb()/rgba() arguments"));
+        }
+        Err(err("expected #hex, 0xhex or rgb()"))
+    }
+
+    pub fn with_alpha(self, a: u8) -> Self {
+        Self { a, ..self }
+    }
+
+    pub fn opaque(self) -> Self {
+        self.with_alpha(255)
+    }
+
+    /// Canonical lowercase hex: `#rrggbb`, or `#rrggbbaa` when not opaque
+    pub fn to_hex(self) -> String {
+        if self.a == 255 {
+            format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+        } else {
+            format!("#{:02x}{:02x}{:02x}{:02x}", self.r, self.g, self.b, self.a)
+        }
+    }
+
+    /// CSS `rgba(r, g, b, alpha)` with an explicit alpha
+    pub fn css_rgba(self, alpha: f32) -> String {
+        format!("rgba({}, {}, {}, {})", self.r, self.g, self.b, alpha)
+    }
+
+    /// WCAG 2.x relative luminance (0.0 black .. 1.0 white), alpha ignored
+    pub fn relative_luminance(self) -> f64 {
+        0.2126 * srgb_to_linear(self.r) + 0.7152 * srgb_to_linear(self.g) + 0.0722 * srgb_to_linear(self.b)
+    }
+
+    /// Hue (degrees), saturation and value, each of the latter 0.0 ..= 1.0
+    pub fn to_hsv(self) -> (f64, f64, f64) {
+        let (r, g, b) = (self.r as f64 / 255.0, self.g as f64 / 255.0, self.b as f64 / 255.0);
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let delta = max - min;
+        let hue = if delta == 0.0 {
+            0.0
+        } else if max == r {
+            60.0 * ((g - b) / delta).rem_euclid(6.0)
+        } else if max == g {
+            60.0 * ((b - r) / delta + 2.0)
+        } else {
+            60.0 * ((r - g) / delta + 4.0)
+        };
+        let sat = if max == 0.0 { 0.0 } else { delta / max };
+        (hue, sat, max)
+    }
+
+    /// CIE L*a*b* (D65), alpha ignored
+    pub fn to_lab(self) -> Lab {
+        let (r, g, b) = (srgb_to_linear(self.r), srgb_to_linear(self.g), srgb_to_linear(self.b));
+        let x = (0.4124564 * r + 0.3575761 * g + 0.1804375 * b) / 0.95047;
+        let y = 0.2126729 * r + 0.7151522 * g + 0.0721750 * b;
+        let z = (0.0193339 * r + 0.1191920 * g + 0.9503041 * b) / 1.08883;
+        let f = |t: f64| {
+            const DELTA: f64 = 6.0 / 29.0;
+            if t > DELTA.powi(3) {
+                t.cbrt()
+            } else {
+                t / (3.0 * DELTA * DELTA) + 4.0 / 29.0
+            }
+        };
+        let (fx, fy, fz) = (f(x), f(y), f(z));
+        Lab {
+            l: 116.0 * fy - 16.0,
+            a: 500.0 * (fx - fy),
+            b: 200.0 * (fy - fz),
+        }
+    }
+
+    /// Black or white, whichever reads better on this color (same rule as 25-starship.sh)
+    pub fn contrast_text(self) -> Rgba {
+        let brightness = (299 * self.r as u32 + 587 * self.g as u32 + 114 * self.b as u32) / 1000;
+        if brightness > 140 {
+            Rgba::BLACK
+        } else {
+            Rgba::WHITE
+        }
+    }
+}
+
+/// A color in CIE L*a*b* space
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Lab {
+    pub l: f64,
+    pub a: f64,
+    pub b: f64,
+}
+
+impl Lab {
+    /// CIEDE2000 color difference (Sharma, Wu & Dalal 2005); ~1.0 is a just
+    /// noticeable difference
+    pub fn delta_e2000(self, other: Lab) -> f64 {
+        use std::f64::consts::PI;
+        let deg = |rad: f64| rad * 180.0 / PI;
+        let rad = |deg: f64| deg * PI / 180.0;
+
+        let c1 = self.a.hypot(self.b);
+        let c2 = other.a.hypot(other.b);
+        let c_bar7 = ((c1 + c2) / 2.0).powi(7);
+        let g = 0.5 * (1.0 - (c_bar7 / (c_bar7 + 25f64.powi(7))).sqrt());
+
+        let a1 = (1.0 + g) * self.a;
+        let a2 = (1.0 + g) * other.a;
+        let c1p = a1.hypot(self.b);
+        let c2p = a2.hypot(other.b);
+        let hue = |b: f64, a: f64| {
+            if a == 0.0 && b == 0.0 {
+                0.0
+            } else {
+                deg(b.atan2(a)).rem_euclid(360.0)
+            }
+        };
+        let h1p = hue(self.b, a1);
+        let h2p = hue(other.b, a2);
+
+        let dl = other.l - self.l;
+        let dc = c2p - c1p;
+        let dh = if c1p * c2p == 0.0 {
+            0.0
+        } else if (h2p - h1p).abs() <= 180.0 {
+            h2p - h1p
+        } else if h2p - h1p > 180.0 {
+            h2p - h1p - 360.0
+        } else {
+            h2p - h1p + 360.0
+        };
+        let dh_big = 2.0 * (c1p * c2p).sqrt() * rad(dh / 2.0).sin();
+
+        let l_bar = (self.l + other.l) / 2.0;
+        let c_bar_p = (c1p + c2p) / 2.0;
+        let h_bar_p = if c1p * c2p == 0.0 {
+            h1p + h2p
+        } else if (h1p - h2p).abs() <= 180.0 {
+            (h1p + h2p) / 2.0
+        } else if h1p + h2p < 360.0 {
+            (h1p + h2p + 360.0) / 2.0
+        } else {
+            (h1p + h2p - 360.0) / 2.0
+        };
+
+        let t = 1.0 - 0.17 * rad(h_bar_p - 30.0).cos()
+            + 0.24 * rad(2.0 * h_bar_p).cos()
+            + 0.32 * rad(3.0 * h_bar_p + 6.0).cos()
+            - 0.20 * rad(4.0 * h_bar_p - 63.0).cos();
+        let d_theta = 30.0 * (-((h_bar_p - 275.0) / 25.0).powi(2)).exp();
+        let c_bar_p7 = c_bar_p.powi(7);
+        let r_c = 2.0 * (c_bar_p7 / (c_bar_p7 + 25f64.powi(7))).sqrt();
+        let l50 = (l_bar - 50.0).powi(2);
+        let s_l = 1.0 + 0.015 * l50 / (20.0 + l50).sqrt();
+        let s_c = 1.0 + 0.045 * c_bar_p;
+        let s_h = 1.0 + 0.015 * c_bar_p * t;
+        let r_t = -(2.0 * rad(d_theta)).sin() * r_c;
+
+        let (tl, tc, th) = (dl / s_l, dc / s_c, dh_big / s_h);
+        (tl * tl + tc * tc + th * th + r_t * tc * th).sqrt()
+    }
+}
+
+/// sRGB channel to linear light (0.0 ..= 1.0)
+fn srgb_to_linear(c: u8) -> f64 {
+    let c = c as f64 / 255.0;
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn parse_hex_digits(hex: &str) -> Option<Rgba> {
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let byte = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    // #rgb: each digit doubled
+    let nibble = |i: usize| u8::from_str_radix(&hex[i..i + 1], 16).ok().map(|n| n * 17);
+    match hex.len() {
+        3 => Some(Rgba::rgb(nibble(0)?, nibble(1)?, nibble(2)?)),
+        6 => Some(Rgba::rgb(byte(0)?, byte(2)?, byte(4)?)),
+        8 => Some(Rgba::rgb(byte(0)?, byte(2)?, byte(4)?).with_alpha(byte(6)?)),
+        _ => None,
+    }
+}
+
+/// `r, g, b[, a]` in decimal (alpha 0.0-1.0), or a single hex run as Hyprland writes it
+fn parse_function_args(args: &str) -> Option<Rgba> {
+    let parts: Vec<&str> = args.split(',').map(str::trim).collect();
+    match parts.as_slice() {
+        [hex] if matches!(hex.len(), 6 | 8) => parse_hex_digits(hex),
+        [r, g, b] => Some(Rgba::rgb(r.parse().ok()?, g.parse().ok()?, b.parse().ok()?)),
+        [r, g, b, a] => {
+            let alpha: f32 = a.parse().ok()?;
+            if !(0.0..=1.0).contains(&alpha) {
+                return None;
+            }
+            Some(Rgba::rgb(r.parse().ok()?, g.parse().ok()?, b.parse().ok()?)
+                .with_alpha((alpha * 255.0).round() as u8))
+        }
+        _ => None,
+    }
+}
+
+impl FromStr for Rgba {
+    type Err = ColorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Rgba::parse(s)
+    }
+}
+
+impl fmt::Display for Rgba {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_hex())
+    }
+}
+
+impl Serialize for Rgba {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_hex())
+    }
+}
+
+impl<'de> Deserialize<'de> for Rgba {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Rgba::parse(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+impl From<Rgba> for ratatui::style::Color {
+    fn from(c: Rgba) -> Self {
+        ratatui::style::Color::Rgb(c.r, c.g, c.b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_every_form() {
+        let navy = Rgba::hex(0x1a1b26);
+        assert_eq!(Rgba::parse("#1A1B26"), Ok(navy));
+        assert_eq!(Rgba::parse("0x1a1b26"), Ok(navy));
+        assert_eq!(Rgba::parse("rgb(26, 27, 38)"), Ok(navy));
+        assert_eq!(Rgba::parse("rgb(1a1b26)"), Ok(navy));
+        assert_eq!(Rgba::parse("#fa0"), Ok(Rgba::hex(0xffaa00)));
+        assert_eq!(Rgba::parse("#1a1b26cc"), Ok(navy.with_alpha(0xcc)));
+        assert_eq!(Rgba::parse("rgba(33ccffee)"), Ok(Rgba::hex(0x33ccff).with_alpha(0xee)));
+        assert_eq!(Rgba::parse("rgba(26, 27, 38, 0.5)"), Ok(navy.with_alpha(128)));
+    }
+
+    #[test]
+    fn canonical_hex_keeps_alpha() {
+        assert_eq!(Rgba::parse("#ABCDEF").unwrap().to_hex(), "#abcdef");
+        assert_eq!(Rgba::parse("#abcdef80").unwrap().to_hex(), "#abcdef80");
+    }
+
+    #[test]
+    fn luminance_extremes() {
+        assert_eq!(Rgba::BLACK.relative_luminance(), 0.0);
+        assert!((Rgba::WHITE.relative_luminance() - 1.0).abs() < 1e-9);
+        // Tokyo Night background is dark, Solarized Light's is light
+        assert!(Rgba::hex(0x1a1b26).relative_luminance() < 0.02);
+        assert!(Rgba::hex(0xfdf6e3).relative_luminance() > 0.9);
+    }
+
+    #[test]
+    fn ciede2000_reference_pairs() {
+        // Test data from Sharma, Wu & Dalal (2005)
+        let pairs = [
+            ((50.0, 2.6772, -79.7751), (50.0, 0.0, -82.7485), 2.0425),
+            ((50.0, 2.5, 0.0), (73.0, 25.0, -18.0), 27.1492),
+            ((50.0, -1.0, 2.0), (50.0, 0.0, 0.0), 2.3669),
+            ((60.2574, -34.0099, 36.2677), (60.4626, -34.1751, 39.4387), 1.2644),
+        ];
+        for ((l1, a1, b1), (l2, a2, b2), expected) in pairs {
+            let de = Lab { l: l1, a: a1, b: b1 }.delta_e2000(Lab { l: l2, a: a2, b: b2 });
+            assert!((de - expected).abs() < 1e-4, "{} != {}", de, expected);
+        }
+    }
+
+    #[test]
+    fn white_is_l100() {
+        let lab = Rgba::WHITE.to_lab();
+        assert!((lab.l - 100.0).abs() < 0.01 && lab.a.abs() < 0.01 && lab.b.abs() < 0.01);
+    }
+
+    #[test]
+    fn rejects_garbage() {
+        for bad in ["red", "#12345", "#gggggg", "0x123", "rgb(300, 0, 0)", "rgba(1, 2, 3, 2)", ""] {
+            assert!(Rgba::parse(bad).is_err(), "{} should not parse", bad);
+        }
+    }
+}
@@ -0,0 +1,55 @@
+//! cruzalex-themes settings from `~/.config/cruzalex/themes.toml`
+//!
+//! ```toml
+//! # GitHub token for the API (raises the rate limit to 5000/h)
+//! token = "ghp_..."
+//! # Most results kept per GitHub topic search (default 300)
+//! search_cap = 500
+//!
+//! # Where remote themes come from; see `sources` for every type
+//! [[source]]
+//! type = "index"
+//! url = "https://intranet.example.com/themes.json"
+//! ```
+
+use crate::app::cruzalex_dir;
+use crate::sources::SourceConfig;
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+use std::path::PathBuf;
+
+/// No `Debug`: it would print the token
+#[derive(Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub token: Option<String>,
+    pub search_cap: Option<usize>,
+    /// `[[source]]` tables; empty means the built-in defaults
+    #[serde(rename = "source")]
+    pub sources: Vec<SourceConfig>,
+}
+
+impl Config {
+    pub fn path() -> Result<PathBuf> {
+        Ok(cruzalex_dir()?.join("themes.toml"))
+    }
+
+    /// Defaults when the file doesn't exist
+    pub fn load() -> Result<Self> {
+        let path = Self::path()?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("reading {}", path.display()))?;
+        // Only the message and line: toml's default rendering quotes the
+        // offending source line, which may be the token
+        toml::from_str(&content).map_err(|e| {
+            let line = e.span().map(|s| content[..s.start].matches('\n').count() + 1);
+            match line {
+                Some(line) => anyhow!("{} line {}: {}", path.display(), line, e.message()),
+                None => anyhow!("{}: {}", path.display(), e.message()),
+            }
+        })
+    }
+}
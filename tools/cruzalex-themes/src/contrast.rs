@@ -0,0 +1,109 @@
+//! WCAG contrast audit of a theme palette
+
+use crate::color::Rgba;
+use crate::theme::ColorPalette;
+
+/// WCAG AA for normal text: foreground and selected text
+pub const TEXT_MIN_RATIO: f64 = 4.5;
+/// WCAG AA for large text / UI components: ANSI colors and the cursor
+pub const ACCENT_MIN_RATIO: f64 = 3.0;
+
+/// One color pair that was measured
+#[derive(Debug, Clone)]
+pub struct ContrastCheck {
+    /// What was compared, e.g. `fg/bg` or `color8`
+    pub label: String,
+    pub ratio: f64,
+    pub min_ratio: f64,
+}
+
+impl ContrastCheck {
+    pub fn passes(&self) -> bool {
+        self.ratio >= self.min_ratio
+    }
+}
+
+/// All contrast checks for one palette
+#[derive(Debug, Clone)]
+pub struct ContrastReport {
+    pub checks: Vec<ContrastCheck>,
+}
+
+impl ContrastReport {
+    pub fn failures(&self) -> impl Iterator<Item = &ContrastCheck> {
+        self.checks.iter().filter(|c| !c.passes())
+    }
+
+    pub fn passes(&self) -> bool {
+        self.failures().next().is_none()
+    }
+}
+
+/// WCAG contrast ratio between two colors (1.0 ..= 21.0)
+pub fn contrast_ratio(a: Rgba, b: Rgba) -> f64 {
+    let (la, lb) = (a.relative_luminance(), b.relative_luminance());
+    let (hi, lo) = if la > lb { (la, lb) } else { (lb, la) };
+    (hi + 0.05) / (lo + 0.05)
+}
+
+/// Audit a palette; None without a background to measure against.
+///
+/// Colors the palette doesn't define are skipped rather than failed.
+pub fn audit(palette: &ColorPalette) -> Option<ContrastReport> {
+    let bg = palette.background?;
+    let mut checks = Vec::new();
+    let mut check = |label: String, fg: Option<Rgba>, against: Rgba, min_ratio: f64| {
+        if let Some(fg) = fg {
+            checks.push(ContrastCheck {
+                label,
+                ratio: contrast_ratio(fg, against),
+                min_ratio,
+            });
+        }
+    };
+
+    check("fg/bg".to_string(), palette.foreground, bg, TEXT_MIN_RATIO);
+    if let Some(sel_bg) = palette.selection_background {
+        check("selection".to_string(), palette.selection_foreground, sel_bg, TEXT_MIN_RATIO);
+    }
+    check("cursor".to_string(), palette.cursor, bg, ACCENT_MIN_RATIO);
+
+    // color0 is meant to blend into the background, so it is not audited
+    let ansi = [
+        palette.color1, palette.color2, palette.color3, palette.color4, palette.color5,
+        palette.color6, palette.color7, palette.color8, palette.color9, palette.color10,
+        palette.color11, palette.color12, palette.color13, palette.color14, palette.color15,
+    ];
+    for (i, color) in ansi.into_iter().enumerate() {
+        check(format!("color{}", i + 1), color, bg, ACCENT_MIN_RATIO);
+    }
+
+    Some(ContrastReport { checks })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ratio_matches_wcag_reference() {
+        assert!((contrast_ratio(Rgba::BLACK, Rgba::WHITE) - 21.0).abs() < 1e-9);
+        // #767676 on white is the classic 4.54:1 AA boundary
+        let grey = contrast_ratio(Rgba::hex(0x767676), Rgba::WHITE);
+        assert!((grey - 4.54).abs() < 0.01, "{}", grey);
+    }
+
+    #[test]
+    fn unreadable_comment_color_fails() {
+        let palette = ColorPalette {
+            background: Some(Rgba::hex(0x1a1b26)),
+            foreground: Some(Rgba::hex(0xc0caf5)),
+            color8: Some(Rgba::hex(0x2a2b36)),
+            ..Default::default()
+        };
+        let report = audit(&palette).unwrap();
+        assert!(!report.passes());
+        let failed: Vec<&str> = report.failures().map(|c| c.label.as_str()).collect();
+        assert_eq!(failed, ["color8"]);
+    }
+}
@@ -0,0 +1,221 @@
+//! colors.toml validation: parse errors, unknown keys, bad values
+
+use crate::palette::FieldError;
+use crate::theme::ColorPalette;
+
+/// Colors every palette is expected to define
+pub const REQUIRED_COLORS: &[&str] = &["foreground", "background"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+/// One problem found in a theme's colors.toml
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// 1-based line in colors.toml, when known
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn error(line: Option<usize>, message: String) -> Self {
+        Self { severity: Severity::Error, line, message }
+    }
+
+    pub fn warning(line: Option<usize>, message: String) -> Self {
+        Self { severity: Severity::Warning, line, message }
+    }
+
+    pub fn describe(&self) -> String {
+        match self.line {
+            Some(line) => format!("line {}: {}", line, self.message),
+            None => self.message.clone(),
+        }
+    }
+}
+
+/// How to treat a colors.toml that isn't valid TOML
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ParseMode {
+    /// Drop the palette on any TOML error
+    Strict,
+    /// Fall back to a line scan; duplicate keys keep the last value
+    #[default]
+    Lenient,
+}
+
+/// Parse colors.toml into a palette, collecting everything wrong with it
+pub fn load_colors(content: &str, mode: ParseMode) -> (Option<ColorPalette>, Vec<Diagnostic>) {
+    let mut diags = Vec::new();
+
+    let entries: Vec<(Option<usize>, String, Option<String>)> = match content.parse::<toml::Table>() {
+        Ok(table) => table
+            .into_iter()
+            .map(|(key, value)| {
+                let line = key_line(content, &key);
+                (line, key, value.as_str().map(str::to_string))
+            })
+            .collect(),
+        Err(e) => {
+            let (line, at) = match e.span() {
+                Some(span) => {
+                    let (line, col) = line_col(content, span.start);
+                    (Some(line), format!(" at column {}", col))
+                }
+                None => (None, String::new()),
+            };
+            let mut message = format!("TOML error{}: {}", at, e.message().trim());
+            if mode == ParseMode::Lenient {
+                message.push_str(" (recovered line by line)");
+            }
+            if mode == ParseMode::Strict {
+                diags.push(Diagnostic::error(line, message));
+                return (None, diags);
+            }
+            diags.push(Diagnostic::warning(line, message));
+            lenient_entries(content, &mut diags)
+        }
+    };
+
+    let mut palette = ColorPalette::default();
+    let mut any = false;
+    for (line, key, value) in entries {
+        let Some(value) = value else {
+            let diag = match palette.field_mut(&key) {
+                Some(_) => Diagnostic::error(line, format!("`{}` must be a string", key)),
+                None => Diagnostic::warning(line, FieldError::UnknownKey(key).to_string()),
+            };
+            diags.push(diag);
+            continue;
+        };
+        match palette.set_field(&key, &value) {
+            Ok(()) => any = true,
+            Err(e @ FieldError::UnknownKey(_)) => diags.push(Diagnostic::warning(line, e.to_string())),
+            Err(e) => diags.push(Diagnostic::error(line, e.to_string())),
+        }
+    }
+
+    for &key in REQUIRED_COLORS {
+        if palette.field_mut(key).is_some_and(|v| v.is_none()) {
+            diags.push(Diagnostic::warning(None, format!("missing required color `{}`", key)));
+        }
+    }
+
+    (any.then_some(palette), diags)
+}
+
+/// Line-based recovery for files the TOML parser rejects
+fn lenient_entries(
+    content: &str,
+    diags: &mut Vec<Diagnostic>,
+) -> Vec<(Option<usize>, String, Option<String>)> {
+    let mut entries: Vec<(Option<usize>, String, Option<String>)> = Vec::new();
+    for (line, key, value) in parse_color_lines(content) {
+        if let Some(prev) = entries.iter_mut().find(|(_, k, _)| *k == key) {
+            diags.push(Diagnostic::warning(
+                Some(line),
+                format!(
+                    "duplicate key `{}` (first on line {}), keeping this one",
+                    key,
+                    prev.0.unwrap_or_default()
+                ),
+            ));
+            *prev = (Some(line), key, Some(value));
+        } else {
+            entries.push((Some(line), key, Some(value)));
+        }
+    }
+    entries
+}
+
+/// Extract `key = "value"` pairs from colors.toml, line by line, with the
+/// 1-based line of each pair.
+///
+/// Deliberately not a full TOML parse: a theme with a duplicate key should
+/// still export its colors. Handles single/double quotes and inline comments.
+pub fn parse_color_lines(content: &str) -> Vec<(usize, String, String)> {
+    let mut vars = Vec::new();
+    for (idx, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with('[') {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let key = key.trim().trim_matches(|c| c == '"' || c == '\'');
+        let value = value.trim();
+        let value = match value.chars().next() {
+            Some(q @ ('"' | '\'')) => value[1..].split(q).next().unwrap_or(""),
+            _ => value.split('#').next().unwrap_or("").trim(),
+        };
+        if key.is_empty()
+            || value.is_empty()
+            || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            continue;
+        }
+        vars.push((idx + 1, key.to_string(), value.to_string()));
+    }
+    vars
+}
+
+/// First line that assigns `key`
+fn key_line(content: &str, key: &str) -> Option<usize> {
+    content.lines().position(|l| {
+        l.trim_start()
+            .trim_start_matches(['"', '\''])
+            .strip_prefix(key)
+            .is_some_and(|rest| rest.trim_start_matches(['"', '\'']).trim_start().starts_with('='))
+    })
+    .map(|i| i + 1)
+}
+
+/// 1-based line and column of a byte offset
+fn line_col(content: &str, offset: usize) -> (usize, usize) {
+    let before = &content[..offset.min(content.len())];
+    let line = before.matches('\n').count() + 1;
+    let col = before.rfind('\n').map_or(before.len(), |nl| before.len() - nl - 1) + 1;
+    (line, col)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Rgba;
+
+    #[test]
+    fn duplicate_key_keeps_last_in_lenient_mode() {
+        let content = "foreground = \"#ffffff\"\nbackground = \"#000000\"\nforeground = \"#c0caf5\"\n";
+        let (palette, diags) = load_colors(content, ParseMode::Lenient);
+        assert_eq!(palette.unwrap().foreground, Some(Rgba::hex(0xc0caf5)));
+        assert_eq!(diags[0].severity, Severity::Warning);
+        assert_eq!(diags[0].line, Some(3));
+        assert!(diags[1].message.contains("first on line 1"));
+
+        let (palette, diags) = load_colors(content, ParseMode::Strict);
+        assert!(palette.is_none());
+        assert_eq!(diags.len(), 1);
+        assert_eq!(diags[0].severity, Severity::Error);
+    }
+
+    #[test]
+    fn unknown_keys_bad_hex_and_missing_colors() {
+        let content = "background = \"#1a1b26\"\nacent = \"#ff00ff\"\ncolor1 = \"red\"\n";
+        let (palette, diags) = load_colors(content, ParseMode::Lenient);
+        assert_eq!(palette.unwrap().background, Some(Rgba::hex(0x1a1b26)));
+        let described: Vec<String> = diags.iter().map(|d| d.describe()).collect();
+        assert_eq!(
+            described,
+            [
+                "line 2: unknown key `acent`",
+                "line 3: `color1`: invalid color `red`: expected #hex, 0xhex or rgb()",
+                "missing required color `foreground`",
+            ]
+        );
+    }
+}
@@ -0,0 +1,379 @@
+//! Where a theme repo is hosted
+//!
+//! Previews, palettes and stars are read from the forge before a theme is
+//! installed, and every forge lays out raw files and its API differently.
+//! Installing is a plain git clone and works from any host.
+//!
+//! Forges are picked by host: github.com, gitlab.com (and `gitlab.*`),
+//! codeberg.org (and `gitea.*` / `forgejo.*`) and git.sr.ht. Anything else is
+//! treated as a bare git remote with no previews or metadata.
+
+use crate::github::{self, RepoMeta};
+use crate::theme::GitHubRepo;
+use chrono::{DateTime, Utc};
+use reqwest::RequestBuilder;
+use serde::Deserialize;
+
+/// Raw-file and metadata endpoints of a code host
+pub trait Forge: Send + Sync {
+    /// The repo part of a URL path on this forge (`owner/repo`), from a repo
+    /// page, clone or raw-file URL
+    fn repo_path(&self, path: &str) -> Option<String> {
+        let mut parts = path.splitn(3, '/');
+        let (owner, repo) = (parts.next()?, parts.next()?);
+        (!owner.is_empty() && !repo.is_empty()).then(|| format!("{}/{}", owner, repo))
+    }
+
+    /// `file` at `branch`, served as-is; None when the forge has no such endpoint
+    fn raw_url(&self, repo: &Repo, branch: &str, file: &str) -> Option<String>;
+
+    /// REST endpoint describing the repo
+    fn metadata_url(&self, repo: &Repo) -> Option<String>;
+
+    /// Stars and metadata from the [`Forge::metadata_url`] response
+    fn parse_metadata(&self, body: &[u8]) -> Option<RepoMeta>;
+
+    /// Add credentials; only GitHub has any, and they must not leak elsewhere
+    fn authorize(&self, request: RequestBuilder) -> RequestBuilder {
+        request
+    }
+}
+
+/// A repository on some forge
+pub struct Repo {
+    pub host: String,
+    /// `owner/repo`, or the whole path on a generic host
+    pub path: String,
+    forge: &'static dyn Forge,
+}
+
+impl Repo {
+    /// Parse an https, ssh (`git@host:path`, `ssh://`) or raw-file URL
+    pub fn parse(url: &str) -> Option<Self> {
+        let url = url.trim();
+        let (host, path) = if let Some(rest) = ["https://", "http://", "ssh://", "git://"]
+            .iter()
+            .find_map(|scheme| url.strip_prefix(scheme))
+        {
+            rest.split_once('/')?
+        } else {
+            // scp-like `git@host:owner/repo`
+            url.split_once(':').filter(|(host, _)| host.contains('@'))?
+        };
+        let host = host.rsplit('@').next()?.to_lowercase();
+        let host = match host.as_str() {
+            "raw.githubusercontent.com" => "github.com".to_string(),
+            _ => host,
+        };
+        let path = path.trim_matches('/');
+        let path = path.strip_suffix(".git").unwrap_or(path);
+
+        let forge = forge_for(&host);
+        Some(Self {
+            path: forge.repo_path(path)?,
+            host,
+            forge,
+        })
+    }
+
+    /// `owner/repo` when this is a GitHub repo
+    pub fn github_path(&self) -> Option<&str> {
+        (self.host == "github.com").then_some(self.path.as_str())
+    }
+
+    pub fn raw_url(&self, branch: &str, file: &str) -> Option<String> {
+        self.forge.raw_url(self, branch, file)
+    }
+
+    /// Raw URLs for a file at the repo root, on main then master
+    pub fn raw_file_urls(&self, file: &str) -> Vec<String> {
+        ["main", "master"]
+            .iter()
+            .filter_map(|branch| self.raw_url(branch, file))
+            .collect()
+    }
+
+    pub fn metadata_url(&self) -> Option<String> {
+        self.forge.metadata_url(self)
+    }
+
+    pub fn authorize(&self, request: RequestBuilder) -> RequestBuilder {
+        self.forge.authorize(request)
+    }
+
+    /// One unconditional metadata request; None on any failure
+    pub async fn fetch_metadata(&self, client: &reqwest::Client) -> Option<RepoMeta> {
+        let request = client
+            .get(self.metadata_url()?)
+            .header("Accept", "application/json")
+            .header("User-Agent", github::USER_AGENT);
+        let response = self
+            .authorize(request)
+            .send()
+            .await
+            .ok()?
+            .error_for_status()
+            .ok()?;
+        self.forge.parse_metadata(&response.bytes().await.ok()?)
+    }
+}
+
+fn forge_for(host: &str) -> &'static dyn Forge {
+    match host {
+        "github.com" => &GitHub,
+        "gitlab.com" => &GitLab,
+        "codeberg.org" => &Gitea,
+        "git.sr.ht" => &SourceHut,
+        h if h.starts_with("gitlab.") => &GitLab,
+        h if h.starts_with("gitea.") || h.starts_with("forgejo.") => &Gitea,
+        _ => &Generic,
+    }
+}
+
+struct GitHub;
+
+impl Forge for GitHub {
+    fn raw_url(&self, repo: &Repo, branch: &str, file: &str) -> Option<String> {
+        Some(format!(
+            "https://raw.githubusercontent.com/{}/{}/{}",
+            repo.path, branch, file
+        ))
+    }
+
+    fn metadata_url(&self, repo: &Repo) -> Option<String> {
+        Some(format!("https://api.github.com/repos/{}", repo.path))
+    }
+
+    fn parse_metadata(&self, body: &[u8]) -> Option<RepoMeta> {
+        let repo: GitHubRepo = serde_json::from_slice(body).ok()?;
+        Some(RepoMeta {
+            stars: repo.stargazers_count,
+            description: repo.description,
+            default_branch: repo.default_branch,
+            pushed_at: repo.pushed_at,
+            topics: repo.topics,
+            license: repo.license.map(|l| l.spdx_id.unwrap_or(l.name)),
+        })
+    }
+
+    /// raw.githubusercontent.com honours the token's rate limit too
+    fn authorize(&self, request: RequestBuilder) -> RequestBuilder {
+        github::authorize(request)
+    }
+}
+
+struct GitLab;
+
+impl Forge for GitLab {
+    /// Subgroups nest, so the repo is everything before the `/-/` separator
+    fn repo_path(&self, path: &str) -> Option<String> {
+        let path = path.split("/-/").next()?;
+        path.contains('/').then(|| path.to_string())
+    }
+
+    fn raw_url(&self, repo: &Repo, branch: &str, file: &str) -> Option<String> {
+        Some(format!(
+            "https://{}/{}/-/raw/{}/{}",
+            repo.host, repo.path, branch, file
+        ))
+    }
+
+    fn metadata_url(&self, repo: &Repo) -> Option<String> {
+        Some(format!(
+            "https://{}/api/v4/projects/{}?license=true",
+            repo.host,
+            repo.path.replace('/', "%2F")
+        ))
+    }
+
+    fn parse_metadata(&self, body: &[u8]) -> Option<RepoMeta> {
+        #[derive(Deserialize)]
+        struct Project {
+            star_count: u32,
+            description: Option<String>,
+            default_branch: Option<String>,
+            last_activity_at: Option<DateTime<Utc>>,
+            #[serde(default)]
+            topics: Vec<String>,
+            license: Option<License>,
+        }
+        #[derive(Deserialize)]
+        struct License {
+            name: String,
+        }
+
+        let project: Project = serde_json::from_slice(body).ok()?;
+        Some(RepoMeta {
+            stars: project.star_count,
+            description: project.description.filter(|d| !d.is_empty()),
+            default_branch: project.default_branch,
+            pushed_at: project.last_activity_at,
+            topics: project.topics,
+            license: project.license.map(|l| l.name),
+        })
+    }
+}
+
+/// Gitea and its Forgejo fork (Codeberg)
+struct Gitea;
+
+impl Forge for Gitea {
+    fn raw_url(&self, repo: &Repo, branch: &str, file: &str) -> Option<String> {
+        Some(format!(
+            "https://{}/{}/raw/branch/{}/{}",
+            repo.host, repo.path, branch, file
+        ))
+    }
+
+    fn metadata_url(&self, repo: &Repo) -> Option<String> {
+        Some(format!("https://{}/api/v1/repos/{}", repo.host, repo.path))
+    }
+
+    fn parse_metadata(&self, body: &[u8]) -> Option<RepoMeta> {
+        #[derive(Deserialize)]
+        struct Repository {
+            stars_count: u32,
+            description: Option<String>,
+            default_branch: Option<String>,
+            updated_at: Option<DateTime<Utc>>,
+            #[serde(default)]
+            topics: Vec<String>,
+            /// SPDX ids; only newer Gitea and Forgejo versions send them
+            #[serde(default)]
+            licenses: Vec<String>,
+        }
+
+        let repo: Repository = serde_json::from_slice(body).ok()?;
+        Some(RepoMeta {
+            stars: repo.stars_count,
+            description: repo.description.filter(|d| !d.is_empty()),
+            default_branch: repo.default_branch,
+            pushed_at: repo.updated_at,
+            topics: repo.topics,
+            license: repo.licenses.into_iter().next(),
+        })
+    }
+}
+
+/// git.sr.ht: raw files, but its API needs a token even to read
+struct SourceHut;
+
+impl Forge for SourceHut {
+    fn raw_url(&self, repo: &Repo, branch: &str, file: &str) -> Option<String> {
+        Some(format!(
+            "https://{}/{}/blob/{}/{}",
+            repo.host, repo.path, branch, file
+        ))
+    }
+
+    fn metadata_url(&self, _repo: &Repo) -> Option<String> {
+        None
+    }
+
+    fn parse_metadata(&self, _body: &[u8]) -> Option<RepoMeta> {
+        None
+    }
+}
+
+/// Any other git remote: clonable, nothing to read beforehand
+struct Generic;
+
+impl Forge for Generic {
+    fn repo_path(&self, path: &str) -> Option<String> {
+        (!path.is_empty()).then(|| path.to_string())
+    }
+
+    fn raw_url(&self, _repo: &Repo, _branch: &str, _file: &str) -> Option<String> {
+        None
+    }
+
+    fn metadata_url(&self, _repo: &Repo) -> Option<String> {
+        None
+    }
+
+    fn parse_metadata(&self, _body: &[u8]) -> Option<RepoMeta> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn preview(url: &str) -> Option<String> {
+        Repo::parse(url)?.raw_url("main", "preview.png")
+    }
+
+    #[test]
+    fn builds_raw_urls_per_forge() {
+        assert_eq!(
+            preview("https://github.com/a/omarchy-nord-theme.git").as_deref(),
+            Some("https://raw.githubusercontent.com/a/omarchy-nord-theme/main/preview.png")
+        );
+        assert_eq!(
+            preview("https://gitlab.com/group/sub/omarchy-nord-theme").as_deref(),
+            Some("https://gitlab.com/group/sub/omarchy-nord-theme/-/raw/main/preview.png")
+        );
+        assert_eq!(
+            preview("git@codeberg.org:a/omarchy-nord-theme.git").as_deref(),
+            Some("https://codeberg.org/a/omarchy-nord-theme/raw/branch/main/preview.png")
+        );
+        assert_eq!(
+            preview("https://git.sr.ht/~a/omarchy-nord-theme").as_deref(),
+            Some("https://git.sr.ht/~a/omarchy-nord-theme/blob/main/preview.png")
+        );
+        assert_eq!(preview("https://git.example.com/themes/nord.git"), None);
+        assert_eq!(
+            Repo::parse("https://git.example.com/themes/nord.git")
+                .unwrap()
+                .path,
+            "themes/nord"
+        );
+    }
+
+    #[test]
+    fn raw_file_urls_point_back_at_their_repo() {
+        for url in [
+            "https://raw.githubusercontent.com/a/nord/master/assets/preview.png",
+            "https://gitlab.com/group/sub/nord/-/raw/main/preview.png",
+            "https://codeberg.org/a/nord/raw/branch/main/preview.png",
+        ] {
+            let repo = Repo::parse(url).unwrap();
+            assert!(repo.path.ends_with("/nord"), "{} -> {}", url, repo.path);
+        }
+        assert_eq!(
+            Repo::parse("https://github.com/a/nord")
+                .unwrap()
+                .github_path(),
+            Some("a/nord")
+        );
+        assert_eq!(
+            Repo::parse("https://gitlab.com/a/nord")
+                .unwrap()
+                .github_path(),
+            None
+        );
+        assert!(Repo::parse("/home/me/src/nord").is_none());
+    }
+
+    #[test]
+    fn parses_gitlab_and_gitea_metadata() {
+        let gitlab = br#"{"id": 1, "star_count": 12, "description": "", "default_branch": "main",
+            "last_activity_at": "2024-05-01T10:00:00.000Z", "topics": ["omarchy"],
+            "license": {"key": "mit", "name": "MIT License"}}"#;
+        let meta = GitLab.parse_metadata(gitlab).unwrap();
+        assert_eq!(
+            (meta.stars, meta.description, meta.license.as_deref()),
+            (12, None, Some("MIT License"))
+        );
+        assert_eq!(meta.topics, ["omarchy"]);
+
+        let gitea =
+            br#"{"id": 2, "stars_count": 3, "description": "Arctic", "default_branch": "trunk",
+            "updated_at": "2024-05-01T10:00:00Z", "licenses": ["MIT"]}"#;
+        let meta = Gitea.parse_metadata(gitea).unwrap();
+        assert_eq!(meta.stars, 3);
+        assert_eq!(meta.default_branch.as_deref(), Some("trunk"));
+        assert_eq!(meta.license.as_deref(), Some("MIT"));
+    }
+}
@@ -0,0 +1,104 @@
+//! Fuzzy subsequence matching for the theme search
+
+/// Points per matched character
+const SCORE_MATCH: i64 = 16;
+/// Bonus when a match directly follows the previous one
+const BONUS_CONSECUTIVE: i64 = 15;
+/// Bonus for matching the first character of a word (`tokyo-night` → `t`, `n`)
+const BONUS_WORD_START: i64 = 10;
+/// Penalty per skipped character between two matches
+const PENALTY_GAP: i64 = 1;
+
+/// A successful match: higher scores are better
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzyMatch {
+    pub score: i64,
+    /// Char indices into the matched text
+    pub positions: Vec<usize>,
+}
+
+fn is_separator(c: char) -> bool {
+    matches!(c, '-' | '_' | ' ' | '.' | '/')
+}
+
+fn fold(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+/// Match `pattern` as a case-insensitive subsequence of `text`.
+///
+/// A forward pass finds where the earliest match ends, then a backward pass
+/// from there picks the tightest window, so `night` in `tokyo-night` lands
+/// on the word rather than on scattered letters before it.
+pub fn fuzzy_match(pattern: &str, text: &str) -> Option<FuzzyMatch> {
+    let pattern: Vec<char> = pattern.chars().map(fold).collect();
+    if pattern.is_empty() {
+        return Some(FuzzyMatch { score: 0, positions: Vec::new() });
+    }
+    let text: Vec<char> = text.chars().collect();
+    let folded: Vec<char> = text.iter().copied().map(fold).collect();
+
+    let mut p = 0;
+    let mut end = None;
+    for (i, &c) in folded.iter().enumerate() {
+        if c == pattern[p] {
+            p += 1;
+            if p == pattern.len() {
+                end = Some(i);
+                break;
+            }
+        }
+    }
+    let end = end?;
+
+    let mut positions = Vec::with_capacity(pattern.len());
+    let mut p = pattern.len();
+    for i in (0..=end).rev() {
+        if folded[i] == pattern[p - 1] {
+            positions.push(i);
+            p -= 1;
+            if p == 0 {
+                break;
+            }
+        }
+    }
+    positions.reverse();
+
+    let mut score = 0;
+    let mut prev: Option<usize> = None;
+    for &i in &positions {
+        score += SCORE_MATCH;
+        if i == 0 || is_separator(text[i - 1]) {
+            score += BONUS_WORD_START;
+        }
+        match prev {
+            Some(j) if i == j + 1 => score += BONUS_CONSECUTIVE,
+            Some(j) => score -= PENALTY_GAP * (i - j - 1) as i64,
+            None => {}
+        }
+        prev = Some(i);
+    }
+    Some(FuzzyMatch { score, positions })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn subsequence_with_positions() {
+        let m = fuzzy_match("tknight", "tokyo-night").unwrap();
+        assert_eq!(m.positions, [0, 2, 6, 7, 8, 9, 10]);
+        assert!(fuzzy_match("night", "Tokyo Night").is_some());
+        assert!(fuzzy_match("nightt", "tokyo-night").is_none());
+    }
+
+    #[test]
+    fn tighter_and_word_aligned_matches_score_higher() {
+        let score = |p, t| fuzzy_match(p, t).unwrap().score;
+        assert!(score("night", "tokyo-night") > score("night", "nord-ice-ghost-t"));
+        assert!(score("rose", "rose-pine") > score("rose", "retro-sage"));
+        // The backward pass keeps the match on the word
+        assert_eq!(fuzzy_match("night", "nordic-night").unwrap().positions, [7, 8, 9, 10, 11]);
+    }
+}
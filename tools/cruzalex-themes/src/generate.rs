@@ -0,0 +1,557 @@
+//! Palette-driven config generators
+//!
+//! Native replacements for the grep/cut pipelines in the theme-set hooks:
+//! every generator reads a parsed [`ColorPalette`], so quoting style and
+//! inline comments in colors.toml no longer matter. The hooks call
+//! `cruzalex-themes generate` and keep their shell version as a fallback.
+//!
+//! Output matches what the hooks wrote. Btop and walker have no generating
+//! hook: btop follows the stock btop color keys, and walker is the stock
+//! `config/walker/style.css` with its colors taken from the palette.
+
+use crate::color::Rgba;
+use crate::theme::ColorPalette;
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+// Fallbacks used by the shell hooks when a color is missing (Tokyo Night)
+const DEFAULT_BACKGROUND: Rgba = Rgba::hex(0x1a1b26);
+const DEFAULT_FOREGROUND: Rgba = Rgba::hex(0xc0caf5);
+const DEFAULT_ACCENT: Rgba = Rgba::hex(0x7aa2f7);
+const DEFAULT_ANSI: [Rgba; 16] = [
+    Rgba::hex(0x15161e), Rgba::hex(0xf7768e), Rgba::hex(0x9ece6a), Rgba::hex(0xe0af68),
+    Rgba::hex(0x7aa2f7), Rgba::hex(0xbb9af7), Rgba::hex(0x7dcfff), Rgba::hex(0xa9b1d6),
+    Rgba::hex(0x414868), Rgba::hex(0xf7768e), Rgba::hex(0x9ece6a), Rgba::hex(0xe0af68),
+    Rgba::hex(0x7aa2f7), Rgba::hex(0xbb9af7), Rgba::hex(0x7dcfff), Rgba::hex(0xc0caf5),
+];
+
+const HEADER: &str = "Auto-generated by cruzalex-themes";
+
+/// An app whose config can be generated from a palette
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Target {
+    Waybar,
+    Btop,
+    Mako,
+    Tmux,
+    Starship,
+    Yazi,
+    Walker,
+}
+
+impl Target {
+    pub const ALL: [Target; 7] = [
+        Target::Waybar,
+        Target::Btop,
+        Target::Mako,
+        Target::Tmux,
+        Target::Starship,
+        Target::Yazi,
+        Target::Walker,
+    ];
+
+    pub fn label(&self) -> &str {
+        match self {
+            Target::Waybar => "waybar",
+            Target::Btop => "btop",
+            Target::Mako => "mako",
+            Target::Tmux => "tmux",
+            Target::Starship => "starship",
+            Target::Yazi => "yazi",
+            Target::Walker => "walker",
+        }
+    }
+
+    /// Output path relative to the XDG config dir
+    pub fn relative_path(&self) -> &str {
+        match self {
+            Target::Waybar => "waybar/colors.css",
+            Target::Btop => "btop/themes/cruzalex.theme",
+            Target::Mako => "mako/config",
+            Target::Tmux => "tmux/theme.conf",
+            Target::Starship => "starship.toml",
+            Target::Yazi => "yazi/theme.toml",
+            Target::Walker => "walker/style.css",
+        }
+    }
+
+    /// Render the file. Mako and starship patch an existing config, so they
+    /// return None when there is nothing to patch (same as the hooks).
+    pub fn render(&self, palette: &ColorPalette, theme_name: &str, existing: Option<&str>) -> Option<String> {
+        let p = Palette::new(palette);
+        match self {
+            Target::Waybar => Some(waybar_css(&p)),
+            Target::Btop => Some(btop_theme(&p, theme_name)),
+            Target::Mako => existing.map(|cfg| mako_config(&p, cfg)),
+            Target::Tmux => Some(tmux_conf(&p, theme_name)),
+            Target::Starship => existing.map(|cfg| starship_config(&p, cfg)),
+            Target::Yazi => Some(yazi_theme(&p)),
+            Target::Walker => Some(walker_css(&p)),
+        }
+    }
+}
+
+/// Generate `targets` into `config_dir`, returning the files written
+pub fn write_targets(
+    targets: &[Target],
+    palette: &ColorPalette,
+    theme_name: &str,
+    config_dir: &Path,
+) -> Result<Vec<PathBuf>> {
+    let mut written = Vec::new();
+    for target in targets {
+        let path = config_dir.join(target.relative_path());
+        let existing = std::fs::read_to_string(&path).ok();
+        let Some(content) = target.render(palette, theme_name, existing.as_deref()) else {
+            continue;
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        std::fs::write(&path, content)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+/// Palette view with the hooks' defaults filled in
+struct Palette {
+    background: Rgba,
+    foreground: Rgba,
+    accent: Rgba,
+    ansi: [Rgba; 16],
+}
+
+impl Palette {
+    fn new(colors: &ColorPalette) -> Self {
+        let ansi_src = [
+            colors.color0, colors.color1, colors.color2, colors.color3,
+            colors.color4, colors.color5, colors.color6, colors.color7,
+            colors.color8, colors.color9, colors.color10, colors.color11,
+            colors.color12, colors.color13, colors.color14, colors.color15,
+        ];
+        let mut ansi = DEFAULT_ANSI;
+        for (slot, value) in ansi.iter_mut().zip(ansi_src) {
+            if let Some(v) = value {
+                *slot = v;
+            }
+        }
+        Self {
+            background: colors.background.unwrap_or(DEFAULT_BACKGROUND),
+            foreground: colors.foreground.unwrap_or(DEFAULT_FOREGROUND),
+            accent: colors.accent.unwrap_or(DEFAULT_ACCENT),
+            ansi,
+        }
+    }
+}
+
+fn waybar_css(p: &Palette) -> String {
+    let mut out = format!("/* {} */\n", HEADER);
+    out.push_str(&format!("@define-color background {};\n", p.background));
+    out.push_str(&format!("@define-color foreground {};\n", p.foreground));
+    out.push_str(&format!("@define-color accent {};\n", p.accent));
+    for (i, color) in p.ansi.iter().enumerate() {
+        out.push_str(&format!("@define-color color{} {};\n", i, color));
+    }
+    out
+}
+
+fn btop_theme(p: &Palette, theme_name: &str) -> String {
+    let a = &p.ansi;
+    let entries = [
+        ("main_bg", p.background),
+        ("main_fg", p.foreground),
+        ("title", p.foreground),
+        ("hi_fg", p.accent),
+        ("selected_bg", a[8]),
+        ("selected_fg", p.foreground),
+        ("inactive_fg", a[8]),
+        ("graph_text", p.foreground),
+        ("meter_bg", a[8]),
+        ("proc_misc", a[6]),
+        ("cpu_box", p.accent),
+        ("mem_box", a[2]),
+        ("net_box", a[5]),
+        ("proc_box", a[4]),
+        ("div_line", a[8]),
+        ("temp_start", a[2]),
+        ("temp_mid", a[3]),
+        ("temp_end", a[1]),
+        ("cpu_start", a[2]),
+        ("cpu_mid", a[3]),
+        ("cpu_end", a[1]),
+        ("free_start", a[2]),
+        ("free_mid", a[10]),
+        ("free_end", a[10]),
+        ("cached_start", a[4]),
+        ("cached_mid", a[12]),
+        ("cached_end", a[12]),
+        ("available_start", a[6]),
+        ("available_mid", a[14]),
+        ("available_end", a[14]),
+        ("used_start", a[3]),
+        ("used_mid", a[11]),
+        ("used_end", a[1]),
+        ("download_start", a[4]),
+        ("download_mid", a[5]),
+        ("download_end", a[13]),
+        ("upload_start", a[2]),
+        ("upload_mid", a[3]),
+        ("upload_end", a[11]),
+    ];
+    let mut out = format!("# {}\n# Theme: {}\n\n", HEADER, theme_name);
+    for (key, color) in entries {
+        out.push_str(&format!("theme[{}]=\"{}\"\n", key, color));
+    }
+    out
+}
+
+/// Rewrite the color lines of an existing mako config (every section)
+fn mako_config(p: &Palette, existing: &str) -> String {
+    let mut out = String::with_capacity(existing.len());
+    for line in existing.lines() {
+        let replaced = match line.split_once('=').map(|(k, _)| k) {
+            Some("background-color") => format!("background-color={}", p.background.with_alpha(0xee)),
+            Some("text-color") => format!("text-color={}", p.foreground),
+            Some("border-color") => format!("border-color={}", p.accent),
+            Some("progress-color") => format!("progress-color=over {}", p.accent.with_alpha(0x66)),
+            _ => line.to_string(),
+        };
+        out.push_str(&replaced);
+        out.push('\n');
+    }
+    out
+}
+
+fn tmux_conf(p: &Palette, theme_name: &str) -> String {
+    let (bg, fg, accent, dim) = (p.background, p.foreground, p.accent, p.ansi[8]);
+    format!(
+        r##"# {HEADER}
+# Theme: {theme_name}
+
+# Status bar colors
+set -g status-style "bg={bg},fg={fg}"
+
+# Window status
+set -g window-status-style "bg={bg},fg={dim}"
+set -g window-status-current-style "bg={accent},fg={bg},bold"
+set -g window-status-activity-style "bg={bg},fg={accent}"
+
+# Pane borders
+set -g pane-border-style "fg={dim}"
+set -g pane-active-border-style "fg={accent}"
+
+# Message style
+set -g message-style "bg={accent},fg={bg}"
+set -g message-command-style "bg={bg},fg={fg}"
+
+# Mode style (copy mode)
+set -g mode-style "bg={accent},fg={bg}"
+
+# Clock
+set -g clock-mode-colour "{accent}"
+
+# Status bar format
+set -g status-left "#[bg={accent},fg={bg},bold] #S #[bg={bg}] "
+set -g status-right "#[fg={fg}] %H:%M #[bg={accent},fg={bg},bold] %d-%b "
+set -g window-status-format " #I:#W "
+set -g window-status-current-format " #I:#W "
+"##
+    )
+}
+
+/// Replace (or append) the `[palettes.cruzalex]` table and select it
+fn starship_config(p: &Palette, existing: &str) -> String {
+    let a = &p.ansi;
+    let bands = [
+        ("orange", a[1]),
+        ("yellow", a[3]),
+        ("aqua", a[2]),
+        ("blue", a[4]),
+        ("bg3", a[5]),
+        ("bg1", a[8]),
+    ];
+
+    let mut palette = String::from("[palettes.cruzalex]\n");
+    palette.push_str("color_fg0 = '#fbf1c7'\n");
+    palette.push_str("color_fg_dark = '#1a1b26'\n");
+    for (band, color) in bands {
+        palette.push_str(&format!("color_fg_{} = '{}'\n", band, color.contrast_text()));
+    }
+    palette.push_str(&format!("color_bg1 = '{}'\n", a[8]));
+    palette.push_str(&format!("color_bg3 = '{}'\n", a[5]));
+    palette.push_str(&format!("color_blue = '{}'\n", a[4]));
+    palette.push_str(&format!("color_aqua = '{}'\n", a[2]));
+    palette.push_str(&format!("color_green = '{}'\n", a[2]));
+    palette.push_str(&format!("color_orange = '{}'\n", a[1]));
+    palette.push_str(&format!("color_purple = '{}'\n", a[5]));
+    palette.push_str(&format!("color_red = '{}'\n", a[1]));
+    palette.push_str(&format!("color_yellow = '{}'\n", a[3]));
+
+    // Drop the old cruzalex table up to the next table header
+    let mut kept = Vec::new();
+    let mut in_ours = false;
+    for line in existing.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') {
+            in_ours = trimmed == "[palettes.cruzalex]";
+        }
+        if in_ours {
+            continue;
+        }
+        if trimmed.starts_with("palette =") || trimmed.starts_with("palette=") {
+            kept.push("palette = 'cruzalex'".to_string());
+        } else {
+            kept.push(line.to_string());
+        }
+    }
+    while kept.last().is_some_and(|l| l.trim().is_empty()) {
+        kept.pop();
+    }
+
+    let mut out = kept.join("\n");
+    out.push_str("\n\n");
+    out.push_str(&palette);
+    out
+}
+
+/// The hook's minimal theme; themes wanting more ship yazi/theme.toml
+fn yazi_theme(p: &Palette) -> String {
+    let accent = p.accent;
+    format!(
+        r#"# {HEADER}
+# For full theming, add yazi/theme.toml to your theme
+
+[manager]
+cwd = {{ fg = "{accent}" }}
+
+[status]
+separator_open = ""
+separator_close = ""
+
+[select]
+border = {{ fg = "{accent}" }}
+"#
+    )
+}
+
+/// The stock walker style with palette colors
+fn walker_css(p: &Palette) -> String {
+    let (bg, fg, accent) = (p.background, p.foreground, p.accent);
+    let window_bg = bg.css_rgba(0.95);
+    let search_bg = p.ansi[0].css_rgba(0.8);
+    let placeholder = fg.css_rgba(0.5);
+    let focus_ring = accent.css_rgba(0.3);
+    let hover = accent.css_rgba(0.15);
+    let selected = accent.css_rgba(0.3);
+    let sub = fg.css_rgba(0.6);
+    let typeahead = fg.css_rgba(0.4);
+    let (red, green, yellow, blue, cyan) = (p.ansi[1], p.ansi[2], p.ansi[3], p.ansi[4], p.ansi[6]);
+    format!(
+        r#"/* {HEADER} */
+
+* {{
+    font-family: "JetBrainsMono Nerd Font", monospace;
+    font-size: 14px;
+}}
+
+#window {{
+    background-color: {window_bg};
+    border: 2px solid {accent};
+    border-radius: 12px;
+}}
+
+#box {{
+    padding: 8px;
+}}
+
+#search {{
+    background-color: {search_bg};
+    color: {fg};
+    border: none;
+    border-radius: 8px;
+    padding: 12px 16px;
+    margin-bottom: 8px;
+    font-size: 16px;
+}}
+
+#search:focus {{
+    outline: none;
+    box-shadow: 0 0 0 2px {focus_ring};
+}}
+
+#search placeholder {{
+    color: {placeholder};
+}}
+
+#list {{
+    background-color: transparent;
+}}
+
+#item {{
+    padding: 10px 14px;
+    margin: 2px 0;
+    border-radius: 6px;
+    background-color: transparent;
+    color: {fg};
+}}
+
+#item:hover {{
+    background-color: {hover};
+}}
+
+#item:selected {{
+    background-color: {selected};
+    color: {accent};
+}}
+
+#item #icon {{
+    margin-right: 12px;
+}}
+
+#item #text {{
+    color: {fg};
+}}
+
+#item #text #label {{
+    font-weight: 500;
+}}
+
+#item #text #sub {{
+    font-size: 12px;
+    color: {sub};
+}}
+
+#item:selected #text #label {{
+    color: {accent};
+}}
+
+#spinner {{
+    color: {accent};
+}}
+
+#typeahead {{
+    color: {typeahead};
+}}
+
+/* Module-specific styling */
+.applications #icon {{
+    color: {cyan};
+}}
+
+.runner #icon {{
+    color: {green};
+}}
+
+.finder #icon {{
+    color: {yellow};
+}}
+
+.websearch #icon {{
+    color: {blue};
+}}
+
+.commands #icon {{
+    color: {red};
+}}
+
+.clipboard #icon {{
+    color: {red};
+}}
+
+.calc #icon {{
+    color: {green};
+}}
+
+.ssh #icon {{
+    color: {cyan};
+}}
+"#
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GOLDEN_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/testdata/generate");
+
+    fn palette() -> ColorPalette {
+        let src = std::fs::read_to_string(Path::new(GOLDEN_DIR).join("colors.toml")).unwrap();
+        toml::from_str(&src).unwrap()
+    }
+
+    /// Compare against `testdata/generate/<file>`; `UPDATE_GOLDEN=1` rewrites it
+    fn check_golden(file: &str, actual: &str) {
+        let path = Path::new(GOLDEN_DIR).join(file);
+        if std::env::var_os("UPDATE_GOLDEN").is_some() {
+            std::fs::write(&path, actual).unwrap();
+            return;
+        }
+        let expected = std::fs::read_to_string(&path)
+            .unwrap_or_else(|e| panic!("missing golden file {}: {}", path.display(), e));
+        assert_eq!(actual, expected, "output differs from {}", path.display());
+    }
+
+    fn render(target: Target, existing: Option<&str>) -> String {
+        target.render(&palette(), "cobalt2", existing).unwrap()
+    }
+
+    #[test]
+    fn waybar_golden() {
+        check_golden("waybar-colors.css", &render(Target::Waybar, None));
+    }
+
+    #[test]
+    fn btop_golden() {
+        check_golden("btop.theme", &render(Target::Btop, None));
+    }
+
+    #[test]
+    fn mako_golden() {
+        let existing = std::fs::read_to_string(Path::new(GOLDEN_DIR).join("mako-input.conf")).unwrap();
+        check_golden("mako-config", &render(Target::Mako, Some(&existing)));
+    }
+
+    #[test]
+    fn mako_without_config_is_skipped() {
+        assert!(Target::Mako.render(&palette(), "cobalt2", None).is_none());
+    }
+
+    #[test]
+    fn tmux_golden() {
+        check_golden("tmux-theme.conf", &render(Target::Tmux, None));
+    }
+
+    #[test]
+    fn starship_golden() {
+        let existing = std::fs::read_to_string(Path::new(GOLDEN_DIR).join("starship-input.toml")).unwrap();
+        check_golden("starship.toml", &render(Target::Starship, Some(&existing)));
+    }
+
+    #[test]
+    fn starship_is_idempotent() {
+        let existing = std::fs::read_to_string(Path::new(GOLDEN_DIR).join("starship-input.toml")).unwrap();
+        let once = render(Target::Starship, Some(&existing));
+        assert_eq!(render(Target::Starship, Some(&once)), once);
+    }
+
+    #[test]
+    fn yazi_golden() {
+        check_golden("yazi-theme.toml", &render(Target::Yazi, None));
+    }
+
+    #[test]
+    fn walker_golden() {
+        check_golden("walker-style.css", &render(Target::Walker, None));
+    }
+
+    #[test]
+    fn missing_colors_fall_back_to_defaults() {
+        let css = Target::Waybar.render(&ColorPalette::default(), "empty", None).unwrap();
+        assert!(css.contains("@define-color background #1a1b26;"));
+        assert!(css.contains("@define-color color8 #414868;"));
+    }
+}
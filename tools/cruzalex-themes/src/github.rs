@@ -0,0 +1,441 @@
+//! GitHub REST API plumbing: conditional requests and rate-limit tracking
+//!
+//! Unauthenticated clients get 60 requests an hour. Every response carries
+//! `X-RateLimit-Remaining` / `X-RateLimit-Reset`; callers stop as soon as the
+//! budget is gone instead of firing off requests that will all fail. Requests
+//! made with a cached ETag come back `304 Not Modified` when nothing changed.
+//!
+//! With a token (see [`token`]) the limit is 5000 requests an hour.
+
+use crate::config::Config;
+use chrono::{DateTime, Local, Utc};
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, ETAG, IF_NONE_MATCH};
+use reqwest::{RequestBuilder, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::OnceLock;
+
+pub const USER_AGENT: &str = "cruzalex-themes/0.1";
+
+/// Where the token came from, for display; the token itself is never shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenSource {
+    Env(&'static str),
+    Config,
+    GhCli,
+}
+
+impl fmt::Display for TokenSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TokenSource::Env(var) => write!(f, "${}", var),
+            TokenSource::Config => write!(f, "themes.toml"),
+            TokenSource::GhCli => write!(f, "gh hosts.yml"),
+        }
+    }
+}
+
+/// A GitHub API token. `Debug` is redacted and there is no `Display`, so it
+/// can't end up in a status message by accident.
+pub struct Token {
+    value: String,
+    pub source: TokenSource,
+}
+
+impl fmt::Debug for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Token")
+            .field("value", &"<redacted>")
+            .field("source", &self.source)
+            .finish()
+    }
+}
+
+impl Token {
+    fn new(value: &str, source: TokenSource) -> Option<Self> {
+        let value = value.trim();
+        (!value.is_empty()).then(|| Self { value: value.to_string(), source })
+    }
+
+    fn header(&self) -> Option<HeaderValue> {
+        let mut header = HeaderValue::from_str(&format!("Bearer {}", self.value)).ok()?;
+        header.set_sensitive(true);
+        Some(header)
+    }
+}
+
+/// The token for this run, resolved once: `$GITHUB_TOKEN` / `$GH_TOKEN`, then
+/// `token` in themes.toml, then the GitHub CLI's hosts.yml
+pub fn token() -> Option<&'static Token> {
+    static TOKEN: OnceLock<Option<Token>> = OnceLock::new();
+    TOKEN.get_or_init(resolve_token).as_ref()
+}
+
+fn resolve_token() -> Option<Token> {
+    for var in ["GITHUB_TOKEN", "GH_TOKEN"] {
+        if let Some(token) = std::env::var(var).ok().and_then(|v| Token::new(&v, TokenSource::Env(var))) {
+            return Some(token);
+        }
+    }
+    // A broken config shouldn't stop the browser; `check` style reporting
+    // of themes.toml is left to the commands that own it
+    if let Some(token) = Config::load()
+        .ok()
+        .and_then(|c| c.token)
+        .and_then(|v| Token::new(&v, TokenSource::Config))
+    {
+        return Some(token);
+    }
+    let gh_dir = std::env::var_os("GH_CONFIG_DIR")
+        .map(std::path::PathBuf::from)
+        .or_else(|| dirs::config_dir().map(|d| d.join("gh")))?;
+    let hosts = std::fs::read_to_string(gh_dir.join("hosts.yml")).ok()?;
+    Token::new(&gh_hosts_token(&hosts)?, TokenSource::GhCli)
+}
+
+/// `oauth_token` under `github.com:` in gh's hosts.yml.
+///
+/// Just enough YAML for that file; gh versions that keep the token in the
+/// system keyring don't write it here at all.
+fn gh_hosts_token(hosts: &str) -> Option<String> {
+    let mut in_github = false;
+    for line in hosts.lines() {
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+        if !line.starts_with(char::is_whitespace) {
+            in_github = line.trim_end() == "github.com:";
+            continue;
+        }
+        if in_github {
+            if let Some(value) = line.trim().strip_prefix("oauth_token:") {
+                return Some(value.trim().trim_matches(['"', '\'']).to_string());
+            }
+        }
+    }
+    None
+}
+
+/// Attach the token, if any. Only for requests to GitHub hosts.
+pub fn authorize(request: RequestBuilder) -> RequestBuilder {
+    match token().and_then(Token::header) {
+        Some(header) => request.header(AUTHORIZATION, header),
+        None => request,
+    }
+}
+
+/// Request budget left, from the `X-RateLimit-*` response headers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    pub remaining: u32,
+    /// Unix seconds when the budget resets
+    pub reset: i64,
+}
+
+impl RateLimit {
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        fn header<T: std::str::FromStr>(headers: &HeaderMap, name: &str) -> Option<T> {
+            headers.get(name)?.to_str().ok()?.trim().parse().ok()
+        }
+        Some(Self {
+            remaining: header(headers, "x-ratelimit-remaining")?,
+            reset: header(headers, "x-ratelimit-reset")?,
+        })
+    }
+
+    pub fn exhausted(&self) -> bool {
+        self.remaining == 0
+    }
+
+    /// Still in effect? Once the reset time passes the limit no longer applies
+    pub fn active(&self) -> bool {
+        self.exhausted() && chrono::Utc::now().timestamp() < self.reset
+    }
+
+    /// Reset time as local `HH:MM`
+    pub fn reset_time(&self) -> String {
+        DateTime::from_timestamp(self.reset, 0)
+            .map(|t| t.with_timezone(&Local).format("%H:%M").to_string())
+            .unwrap_or_else(|| "?".to_string())
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum GitHubError {
+    #[error("rate limited until {}", .0.reset_time())]
+    RateLimited(RateLimit),
+    #[error("GitHub API error: {0}")]
+    Status(StatusCode),
+    #[error("the GitHub GraphQL API needs a token")]
+    Unauthenticated,
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+}
+
+/// Result of a conditional GET
+#[derive(Debug)]
+pub enum Fetched<T> {
+    /// New content and the ETag to send next time
+    Modified(T, Option<String>),
+    /// The cached copy for the ETag we sent is still current
+    NotModified,
+}
+
+/// A GET against the API; `etag` turns it into a conditional request.
+///
+/// Returns the rate limit seen on the response alongside the result, so
+/// callers can stop before the next request when it hits zero.
+pub async fn get_json<T: DeserializeOwned>(
+    client: &reqwest::Client,
+    url: &str,
+    query: &[(&str, &str)],
+    etag: Option<&str>,
+) -> (Result<Fetched<T>, GitHubError>, Option<RateLimit>) {
+    let mut request = authorize(client.get(url))
+        .query(query)
+        .header("Accept", "application/vnd.github+json")
+        .header("User-Agent", USER_AGENT);
+    if let Some(etag) = etag {
+        request = request.header(IF_NONE_MATCH, etag);
+    }
+
+    let response = match request.send().await {
+        Ok(response) => response,
+        Err(e) => return (Err(e.into()), None),
+    };
+    let limit = RateLimit::from_headers(response.headers());
+    let status = response.status();
+
+    if status == StatusCode::NOT_MODIFIED {
+        return (Ok(Fetched::NotModified), limit);
+    }
+    if matches!(status, StatusCode::FORBIDDEN | StatusCode::TOO_MANY_REQUESTS) {
+        if let Some(exhausted) = limit.filter(RateLimit::exhausted) {
+            return (Err(GitHubError::RateLimited(exhausted)), limit);
+        }
+    }
+    if !status.is_success() {
+        return (Err(GitHubError::Status(status)), limit);
+    }
+
+    let etag = response
+        .headers()
+        .get(ETAG)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+    let result = match response.json().await {
+        Ok(body) => Ok(Fetched::Modified(body, etag)),
+        Err(e) => Err(e.into()),
+    };
+    (result, limit)
+}
+
+/// Repos per GraphQL request; well under the query complexity limits
+pub const GRAPHQL_BATCH: usize = 50;
+
+/// Repository metadata from the GraphQL API
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RepoMeta {
+    pub stars: u32,
+    pub description: Option<String>,
+    pub default_branch: Option<String>,
+    pub pushed_at: Option<DateTime<Utc>>,
+    pub topics: Vec<String>,
+    /// SPDX id, or the license name when GitHub can't map it to one
+    pub license: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RepoNode {
+    stargazer_count: u32,
+    description: Option<String>,
+    default_branch_ref: Option<Named>,
+    pushed_at: Option<DateTime<Utc>>,
+    repository_topics: TopicConnection,
+    license_info: Option<LicenseNode>,
+}
+
+#[derive(Deserialize)]
+struct Named {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct TopicConnection {
+    nodes: Vec<TopicNode>,
+}
+
+#[derive(Deserialize)]
+struct TopicNode {
+    topic: Named,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LicenseNode {
+    spdx_id: Option<String>,
+    name: String,
+}
+
+impl From<RepoNode> for RepoMeta {
+    fn from(node: RepoNode) -> Self {
+        Self {
+            stars: node.stargazer_count,
+            description: node.description.filter(|d| !d.trim().is_empty()),
+            default_branch: node.default_branch_ref.map(|b| b.name),
+            pushed_at: node.pushed_at,
+            topics: node.repository_topics.nodes.into_iter().map(|n| n.topic.name).collect(),
+            license: node.license_info.map(|l| match l.spdx_id {
+                Some(id) if id != "NOASSERTION" => id,
+                _ => l.name,
+            }),
+        }
+    }
+}
+
+/// One aliased `repository` field per `owner/name`: `r0`, `r1`, ...
+fn repo_metadata_query(repos: &[&str]) -> String {
+    let mut query = String::from("query {");
+    for (i, path) in repos.iter().enumerate() {
+        let Some((owner, name)) = path.split_once('/') else {
+            continue;
+        };
+        // JSON string literals are valid GraphQL strings, escaping included
+        query.push_str(&format!(
+            " r{}: repository(owner: {}, name: {}) {{ stargazerCount description \
+             defaultBranchRef {{ name }} pushedAt \
+             repositoryTopics(first: 20) {{ nodes {{ topic {{ name }} }} }} \
+             licenseInfo {{ spdxId name }} }}",
+            i,
+            serde_json::Value::from(owner),
+            serde_json::Value::from(name),
+        ));
+    }
+    query.push_str(" }");
+    query
+}
+
+/// Metadata for up to [`GRAPHQL_BATCH`] `owner/name` repos in one request.
+///
+/// The GraphQL API requires a token. Repos that don't exist (or were renamed
+/// away) are simply missing from the result.
+pub async fn fetch_repo_metadata(
+    client: &reqwest::Client,
+    repos: &[&str],
+) -> (Result<HashMap<String, RepoMeta>, GitHubError>, Option<RateLimit>) {
+    #[derive(Deserialize)]
+    struct Response {
+        data: Option<HashMap<String, Option<RepoNode>>>,
+    }
+
+    let Some(header) = token().and_then(Token::header) else {
+        return (Err(GitHubError::Unauthenticated), None);
+    };
+    let body = serde_json::json!({ "query": repo_metadata_query(repos) });
+    let response = match client
+        .post("https://api.github.com/graphql")
+        .header(AUTHORIZATION, header)
+        .header("User-Agent", USER_AGENT)
+        .json(&body)
+        .send()
+        .await
+    {
+        Ok(response) => response,
+        Err(e) => return (Err(e.into()), None),
+    };
+    let limit = RateLimit::from_headers(response.headers());
+    let status = response.status();
+    if let Some(exhausted) = limit.filter(RateLimit::exhausted) {
+        if !status.is_success() {
+            return (Err(GitHubError::RateLimited(exhausted)), limit);
+        }
+    }
+    if !status.is_success() {
+        return (Err(GitHubError::Status(status)), limit);
+    }
+
+    // Partial data comes with an `errors` list (e.g. NOT_FOUND per alias);
+    // whatever resolved is still good
+    let data = match response.json::<Response>().await {
+        Ok(response) => response.data.unwrap_or_default(),
+        Err(e) => return (Err(e.into()), limit),
+    };
+    let mut metadata = HashMap::new();
+    for (alias, node) in data {
+        let repo = alias
+            .strip_prefix('r')
+            .and_then(|i| i.parse::<usize>().ok())
+            .and_then(|i| repos.get(i));
+        if let (Some(repo), Some(node)) = (repo, node) {
+            metadata.insert(repo.to_string(), RepoMeta::from(node));
+        }
+    }
+    (Ok(metadata), limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn reads_rate_limit_headers() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(RateLimit::from_headers(&headers), None);
+
+        headers.insert("x-ratelimit-remaining", HeaderValue::from_static("0"));
+        headers.insert("x-ratelimit-reset", HeaderValue::from_static("1700000000"));
+        let limit = RateLimit::from_headers(&headers).unwrap();
+        assert!(limit.exhausted());
+        // Long past, so no longer in effect
+        assert!(!limit.active());
+        assert_eq!(limit.reset_time().len(), 5);
+    }
+
+    #[test]
+    fn reads_gh_hosts_token_for_github_only() {
+        let hosts = "\
+ghe.example.com:
+    oauth_token: gho_enterprise
+github.com:
+    user: octocat
+    oauth_token: gho_abc123
+    git_protocol: ssh
+";
+        assert_eq!(gh_hosts_token(hosts).as_deref(), Some("gho_abc123"));
+        assert_eq!(gh_hosts_token("github.com:\n    user: octocat\n"), None);
+    }
+
+    #[test]
+    fn batches_repos_into_one_aliased_query() {
+        let query = repo_metadata_query(&["folke/tokyonight.nvim", "bad\"owner/x"]);
+        assert!(query.contains(r#"r0: repository(owner: "folke", name: "tokyonight.nvim")"#));
+        assert!(query.contains(r#"r1: repository(owner: "bad\"owner", name: "x")"#));
+
+        let node: RepoNode = serde_json::from_str(
+            r#"{"stargazerCount": 7, "description": "", "defaultBranchRef": {"name": "main"},
+                "pushedAt": "2025-03-01T12:00:00Z",
+                "repositoryTopics": {"nodes": [{"topic": {"name": "omarchy-theme"}}]},
+                "licenseInfo": {"spdxId": "NOASSERTION", "name": "Other"}}"#,
+        )
+        .unwrap();
+        let meta = RepoMeta::from(node);
+        assert_eq!(meta.stars, 7);
+        assert_eq!(meta.description, None);
+        assert_eq!(meta.default_branch.as_deref(), Some("main"));
+        assert_eq!(meta.topics, ["omarchy-theme"]);
+        assert_eq!(meta.license.as_deref(), Some("Other"));
+    }
+
+    #[test]
+    fn token_is_redacted() {
+        let token = Token::new(" ghp_secret\n", TokenSource::Config).unwrap();
+        let shown = format!("{:?}", token);
+        assert!(!shown.contains("ghp_secret"), "{}", shown);
+        assert!(token.header().unwrap().is_sensitive());
+        assert!(Token::new("  ", TokenSource::Config).is_none());
+    }
+}
@@ -0,0 +1,317 @@
+//! Installing a theme from a directory or archive instead of a git remote
+//!
+//! `--from` takes a theme directory, a `.zip` / `.tar.gz` file, or an
+//! http(s) URL to one. Archives are unpacked into a staging directory under
+//! `themes/` and only moved into place once they hold a theme (see
+//! [`THEME_FILES`]). An entry with an absolute path, a `..` component or a
+//! link fails the whole install rather than being skipped.
+
+use crate::github;
+use crate::theme::{check_name, is_theme_dir, THEME_FILES};
+use anyhow::{bail, Context, Result};
+use flate2::read::GzDecoder;
+use std::io::Cursor;
+use std::path::{Component, Path, PathBuf};
+
+/// Install from `from` into `themes_dir`, as `name` or a name derived from
+/// the source. Returns the new theme directory.
+pub async fn install_from(from: &str, themes_dir: &Path, name: Option<&str>) -> Result<PathBuf> {
+    std::fs::create_dir_all(themes_dir)
+        .with_context(|| format!("creating {}", themes_dir.display()))?;
+    // Dot-prefixed so load_local_themes never lists a half-unpacked theme
+    let staging = themes_dir.join(format!(".install-{}", std::process::id()));
+    let result = stage_and_move(from, themes_dir, &staging, name).await;
+    let _ = std::fs::remove_dir_all(&staging);
+    result
+}
+
+pub fn is_url(from: &str) -> bool {
+    from.starts_with("https://") || from.starts_with("http://")
+}
+
+async fn stage_and_move(
+    from: &str,
+    themes_dir: &Path,
+    staging: &Path,
+    name: Option<&str>,
+) -> Result<PathBuf> {
+    if staging.exists() {
+        std::fs::remove_dir_all(staging)?;
+    }
+    std::fs::create_dir(staging)?;
+
+    if is_url(from) {
+        unpack(&download(from).await?, staging)?;
+    } else {
+        let path = Path::new(from);
+        if path.is_dir() {
+            // Check before copying: a mistyped path could be a huge tree
+            if !is_theme_dir(path) {
+                bail!(
+                    "{} is not a theme: expected one of {}",
+                    from,
+                    THEME_FILES.join(", ")
+                );
+            }
+            copy_dir(path, staging)?;
+        } else {
+            let bytes = std::fs::read(path).with_context(|| format!("reading {}", from))?;
+            unpack(&bytes, staging)?;
+        }
+    }
+
+    let root = theme_root(staging)?;
+    let name = match name {
+        Some(name) => name.to_string(),
+        // A wrapping directory ("omarchy-nord-theme-main/") names it better
+        // than a URL ending in "main.zip"
+        None => (root != staging)
+            .then(|| theme_name(&root.file_name()?.to_string_lossy()))
+            .flatten()
+            .or_else(|| theme_name(from))
+            .context("can't tell what to call this theme; pass --name")?,
+    };
+    check_name(&name)?;
+
+    let dest = themes_dir.join(&name);
+    if dest.exists() {
+        bail!("'{}' is already installed at {}", name, dest.display());
+    }
+    std::fs::rename(&root, &dest).with_context(|| format!("moving theme to {}", dest.display()))?;
+    Ok(dest)
+}
+
+async fn download(url: &str) -> Result<Vec<u8>> {
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(60))
+        .build()?;
+    let response = client
+        .get(url)
+        .header("User-Agent", github::USER_AGENT)
+        .send()
+        .await
+        .with_context(|| format!("downloading {}", url))?
+        .error_for_status()?;
+    Ok(response.bytes().await?.to_vec())
+}
+
+/// Unpack a zip or tar.gz, told apart by their magic bytes
+fn unpack(bytes: &[u8], dest: &Path) -> Result<()> {
+    if bytes.starts_with(b"PK\x03\x04") || bytes.starts_with(b"PK\x05\x06") {
+        unpack_zip(bytes, dest)
+    } else if bytes.starts_with(b"\x1f\x8b") {
+        unpack_tar_gz(bytes, dest)
+    } else {
+        bail!("not a directory, .zip or .tar.gz")
+    }
+}
+
+fn unpack_zip(bytes: &[u8], dest: &Path) -> Result<()> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).context("reading zip")?;
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i)?;
+        let path = safe_path(Path::new(file.name()))?;
+        // Finder's resource forks, added to every zip made on a Mac
+        if path.starts_with("__MACOSX") || path.as_os_str().is_empty() {
+            continue;
+        }
+        if file.is_symlink() {
+            bail!("archive entry '{}' is a link", file.name());
+        }
+        let target = dest.join(&path);
+        if file.is_dir() {
+            std::fs::create_dir_all(&target)?;
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut out = std::fs::File::create(&target)?;
+        std::io::copy(&mut file, &mut out)
+            .with_context(|| format!("extracting {}", file.name()))?;
+    }
+    Ok(())
+}
+
+fn unpack_tar_gz(bytes: &[u8], dest: &Path) -> Result<()> {
+    let mut archive = tar::Archive::new(GzDecoder::new(bytes));
+    for entry in archive.entries().context("reading tar.gz")? {
+        let mut entry = entry?;
+        let name = entry.path()?.into_owned();
+        let path = safe_path(&name)?;
+        if path.as_os_str().is_empty() {
+            continue;
+        }
+        let kind = entry.header().entry_type();
+        let target = dest.join(&path);
+        if kind.is_dir() {
+            std::fs::create_dir_all(&target)?;
+        } else if kind.is_file() {
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            entry
+                .unpack(&target)
+                .with_context(|| format!("extracting {}", name.display()))?;
+        } else if kind.is_symlink() || kind.is_hard_link() {
+            bail!("archive entry '{}' is a link", name.display());
+        }
+        // Anything else (pax headers, fifos) carries no theme files
+    }
+    Ok(())
+}
+
+/// The relative path an archive entry may be written to, or an error for
+/// one that could land outside the destination
+fn safe_path(name: &Path) -> Result<PathBuf> {
+    let mut path = PathBuf::new();
+    for component in name.components() {
+        match component {
+            Component::Normal(part) => path.push(part),
+            Component::CurDir => {}
+            Component::ParentDir => bail!("archive entry '{}' contains '..'", name.display()),
+            Component::RootDir | Component::Prefix(_) => {
+                bail!("archive entry '{}' is an absolute path", name.display())
+            }
+        }
+    }
+    Ok(path)
+}
+
+/// Copy a theme directory, links included as links
+fn copy_dir(src: &Path, dest: &Path) -> Result<()> {
+    for entry in walkdir::WalkDir::new(src).min_depth(1) {
+        let entry = entry?;
+        let target = dest.join(entry.path().strip_prefix(src)?);
+        let kind = entry.file_type();
+        if kind.is_dir() {
+            std::fs::create_dir_all(&target)?;
+        } else if kind.is_symlink() {
+            std::os::unix::fs::symlink(std::fs::read_link(entry.path())?, &target)?;
+        } else {
+            std::fs::copy(entry.path(), &target)
+                .with_context(|| format!("copying {}", entry.path().display()))?;
+        }
+    }
+    Ok(())
+}
+
+/// Where the theme files are: the top level, or the one directory an
+/// archive wraps them in (GitHub's `repo-main/`)
+fn theme_root(staging: &Path) -> Result<PathBuf> {
+    if is_theme_dir(staging) {
+        return Ok(staging.to_path_buf());
+    }
+    let entries: Vec<PathBuf> = std::fs::read_dir(staging)?
+        .flatten()
+        .map(|e| e.path())
+        .collect();
+    match entries.as_slice() {
+        [only] if only.is_dir() && is_theme_dir(only) => Ok(only.clone()),
+        _ => bail!(
+            "not a theme: expected one of {} at the top level",
+            THEME_FILES.join(", ")
+        ),
+    }
+}
+
+/// Theme directory name from a path, archive or URL, named the way
+/// catalogue themes are: "omarchy-nord-theme-main.zip" -> "nord"
+fn theme_name(source: &str) -> Option<String> {
+    let base = source.trim_end_matches('/').rsplit('/').next()?;
+    let base = base.split(['?', '#']).next()?;
+    let base = [".tar.gz", ".tgz", ".zip"]
+        .iter()
+        .find_map(|ext| base.strip_suffix(ext))
+        .unwrap_or(base);
+    let base = ["-main", "-master"]
+        .iter()
+        .find_map(|branch| base.strip_suffix(branch))
+        .unwrap_or(base);
+    let base = base.strip_prefix("omarchy-").unwrap_or(base);
+    let base = base.strip_suffix("-theme").unwrap_or(base);
+    (!base.is_empty() && base != "." && base != "..").then(|| base.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn zip_of(files: &[(&str, &str)]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = zip::write::SimpleFileOptions::default();
+        for (name, content) in files {
+            writer.start_file(*name, options).unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn unpacks_a_wrapped_zip_into_a_theme() {
+        let tmp = tempfile::tempdir().unwrap();
+        let staging = tmp.path();
+        let bytes = zip_of(&[
+            (
+                "omarchy-nord-theme-main/colors.toml",
+                "background = \"#2e3440\"\n",
+            ),
+            ("omarchy-nord-theme-main/backgrounds/1.png", "png"),
+            ("__MACOSX/omarchy-nord-theme-main/._colors.toml", ""),
+        ]);
+        unpack(&bytes, staging).unwrap();
+        let root = theme_root(staging).unwrap();
+        assert!(root.join("backgrounds/1.png").is_file());
+        assert_eq!(
+            theme_name(&root.file_name().unwrap().to_string_lossy()).as_deref(),
+            Some("nord")
+        );
+    }
+
+    #[test]
+    fn rejects_entries_that_escape() {
+        let tmp = tempfile::tempdir().unwrap();
+        let staging = tmp.path();
+        let err = unpack(&zip_of(&[("../evil.sh", "rm -rf ~")]), staging).unwrap_err();
+        assert!(err.to_string().contains(".."), "{}", err);
+        assert!(!staging.parent().unwrap().join("evil.sh").exists());
+
+        // tar::Builder refuses to write such paths, so poke the name in directly
+        let mut header = tar::Header::new_old();
+        header.as_old_mut().name[..11].copy_from_slice(b"/etc/passwd");
+        header.set_size(4);
+        header.set_cksum();
+        let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(
+            Vec::new(),
+            Default::default(),
+        ));
+        builder.append(&header, &b"root"[..]).unwrap();
+        let bytes = builder.into_inner().unwrap().finish().unwrap();
+        let err = unpack(&bytes, staging).unwrap_err();
+        assert!(err.to_string().contains("absolute"), "{}", err);
+
+        assert!(safe_path(Path::new("a/./b/../c")).is_err());
+        assert_eq!(safe_path(Path::new("./a/b")).unwrap(), PathBuf::from("a/b"));
+    }
+
+    #[test]
+    fn needs_theme_files_at_the_top() {
+        let tmp = tempfile::tempdir().unwrap();
+        let staging = tmp.path();
+        unpack(
+            &zip_of(&[("docs/README.md", "hi"), ("src/colors.toml", "")]),
+            staging,
+        )
+        .unwrap();
+        assert!(theme_root(staging).is_err());
+        assert_eq!(
+            theme_name("/home/me/Downloads/tokyo-night.tar.gz").as_deref(),
+            Some("tokyo-night")
+        );
+        assert_eq!(
+            theme_name("https://example.com/omarchy-kanso-theme.zip?dl=1").as_deref(),
+            Some("kanso")
+        );
+    }
+}
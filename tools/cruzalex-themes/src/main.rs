@@ -3,12 +3,31 @@
 //! Browse, preview, and install Omarchy-compatible themes
 
 mod app;
+mod apply;
+mod awesome;
+mod catalogue;
+mod cli;
+mod color;
+mod config;
+mod contrast;
+mod diagnostics;
+mod forge;
+mod fuzzy;
+mod generate;
+mod github;
+mod install;
+mod palette;
+mod plan;
+mod query;
+mod similar;
+mod sources;
 mod theme;
 mod ui;
+mod update;
 
 use anyhow::Result;
-use app::App;
-use clap::Parser;
+use app::{App, FilterMode, SortMode};
+use clap::{Parser, Subcommand};
 use crossterm::{
     event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind, KeyModifiers},
     execute,
@@ -20,19 +39,136 @@ use std::io;
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
-    /// Refresh theme list from remote sources
-    #[arg(short, long)]
+    /// Wait for a fresh theme list instead of starting from the cache
+    #[arg(short, long, conflicts_with = "offline")]
     refresh: bool,
 
+    /// Never touch the network; remote themes come from the cached catalogue
+    #[arg(long, global = true)]
+    offline: bool,
+
     /// Show only installed themes
     #[arg(short, long)]
     installed: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Print the theme catalogue without starting the TUI
+    List {
+        /// Output format
+        #[arg(long, value_enum, default_value_t = cli::OutputFormat::Plain)]
+        format: cli::OutputFormat,
+
+        /// Which themes to include
+        #[arg(long, value_enum, default_value_t = FilterMode::All)]
+        filter: FilterMode,
+
+        /// Sort order
+        #[arg(long, value_enum, default_value_t = SortMode::Name)]
+        sort: SortMode,
+
+        /// Only list locally installed themes (skip remote sources)
+        #[arg(short, long)]
+        local: bool,
+    },
+
+    /// Apply an installed theme and run its hooks
+    Apply {
+        /// Theme name (directory under themes/)
+        name: String,
+
+        /// Per-hook timeout in seconds
+        #[arg(long, default_value_t = apply::DEFAULT_HOOK_TIMEOUT.as_secs())]
+        timeout: u64,
+
+        /// Restore the previous theme if any hook fails
+        #[arg(long)]
+        rollback: bool,
+
+        /// Show what would change (files, hooks, processes) without applying
+        #[arg(long, conflicts_with = "rollback")]
+        dry_run: bool,
+    },
+
+    /// Generate app configs (waybar, btop, mako, ...) from a theme's palette
+    Generate {
+        /// Theme name (directory under themes/)
+        name: String,
+
+        /// Config root to write into (defaults to ~/.config)
+        #[arg(long)]
+        out: Option<std::path::PathBuf>,
+
+        /// Only generate these apps (comma separated)
+        #[arg(long, value_enum, value_delimiter = ',')]
+        only: Vec<generate::Target>,
+    },
+
+    /// Install a theme from a directory or archive instead of a git remote
+    Install {
+        /// Theme directory, .zip or .tar.gz file, or an http(s) URL to an archive
+        #[arg(long, value_name = "PATH|URL")]
+        from: String,
+
+        /// Directory name under themes/ (derived from the source by default)
+        #[arg(long)]
+        name: Option<String>,
+    },
+
+    /// Fast-forward installed git themes, showing the incoming commits
+    Update {
+        /// Theme name (directory under themes/)
+        #[arg(required_unless_present = "all", conflicts_with = "all")]
+        name: Option<String>,
+
+        /// Update every installed theme that is a git clone
+        #[arg(long)]
+        all: bool,
+
+        /// Only fetch and list the incoming commits
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Validate colors.toml of installed themes
+    Check {
+        /// Theme name (all installed themes if omitted)
+        name: Option<String>,
+
+        /// Treat any TOML error as fatal instead of recovering line by line
+        #[arg(long)]
+        strict: bool,
+    },
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let args = Args::parse();
 
+    if let Some(command) = args.command {
+        return match command {
+            Command::List { format, filter, sort, local } => {
+                cli::list(format, filter, sort, local, args.offline).await
+            }
+            Command::Apply { name, dry_run: true, .. } => cli::plan(&name),
+            Command::Apply { name, timeout, rollback, .. } => {
+                cli::apply(&name, std::time::Duration::from_secs(timeout), rollback).await
+            }
+            Command::Install { from, name } => {
+                cli::install(&from, name.as_deref(), args.offline).await
+            }
+            Command::Update { name, dry_run, .. } => {
+                cli::update(name.as_deref(), dry_run, args.offline)
+            }
+            Command::Check { name, strict } => cli::check(name.as_deref(), strict),
+            Command::Generate { name, out, only } => cli::generate(&name, out, &only),
+        };
+    }
+
     // Setup terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -42,17 +178,25 @@ async fn main() -> Result<()> {
 
     // Create app and run
     let mut app = App::new().await?;
+    app.offline = args.offline;
 
     // Initialize image picker for terminal graphics protocol detection
     app.init_image_picker();
     // Load preview for initial selection
     app.load_selected_preview();
 
-    // Auto-refresh themes from GitHub on startup (unless --installed flag)
+    // Start from the cached catalogue and refresh it in the background
+    // (unless --installed flag)
     if !args.installed {
-        app.refresh_remote_themes().await?;
-        // Fetch GitHub stars in background
-        app.fetch_stars();
+        if args.refresh {
+            app.refresh_remote_themes().await?;
+            // Fetch GitHub stars in background
+            app.fetch_stars();
+            app.prefetch_palettes();
+        } else {
+            app.load_cached_catalogue();
+            app.refresh_in_background();
+        }
     }
 
     if args.installed {
@@ -106,13 +250,21 @@ async fn run_app<B: ratatui::backend::Backend>(
                     continue;
                 }
 
-                // Modal handling — About and Zoom intercept most keys
-                if app.about_open || app.zoom_open {
+                // Modal handling — About, Zoom and Hooks intercept most keys
+                if app.any_modal_open() {
                     match key.code {
+                        KeyCode::Char('u') if app.hooks_open => app.revert_apply(),
+                        KeyCode::Enter if app.plan_open => {
+                            app.close_modals();
+                            app.apply_plan();
+                        }
+                        KeyCode::Enter if app.update_open => app.apply_update(),
                         KeyCode::Esc
                         | KeyCode::Char('q')
                         | KeyCode::Char('?')
                         | KeyCode::Char('z')
+                        | KeyCode::Char('h')
+                        | KeyCode::Char('d')
                         | KeyCode::Enter => {
                             app.close_modals();
                         }
@@ -137,10 +289,14 @@ async fn run_app<B: ratatui::backend::Backend>(
                     (_, KeyCode::End) | (_, KeyCode::Char('G')) => app.last(),
 
                     // Actions
-                    (_, KeyCode::Enter) => { app.apply_theme()?; }
+                    (_, KeyCode::Enter) => app.apply_theme(),
                     (_, KeyCode::Char('i')) => app.install_theme(),
                     (_, KeyCode::Char('x')) => { app.delete_theme()?; }
-                    (_, KeyCode::Char('r')) => { app.refresh_remote_themes().await?; }
+                    (_, KeyCode::Char('r')) => {
+                        app.refresh_in_background();
+                        app.check_updates();
+                    }
+                    (_, KeyCode::Char('U')) => app.show_update(),
 
                     // Favorites
                     (_, KeyCode::Char('f')) => app.toggle_favorite(),
@@ -152,10 +308,17 @@ async fn run_app<B: ratatui::backend::Backend>(
                     (_, KeyCode::Tab) => app.cycle_filter(),
                     (_, KeyCode::Char('s')) => app.cycle_sort(),
                     (_, KeyCode::Char('p')) => app.toggle_preview(),
+                    (_, KeyCode::Char('m')) => app.toggle_similar(),
+                    (_, KeyCode::Char(c @ '1'..='9')) if app.show_similar => {
+                        app.jump_to_similar(c as usize - '0' as usize);
+                    }
 
                     // Modals
                     (_, KeyCode::Char('?')) => app.toggle_about(),
                     (_, KeyCode::Char('z')) => app.toggle_zoom(),
+                    (_, KeyCode::Char('h')) => app.toggle_hooks(),
+                    (_, KeyCode::Char('u')) => app.revert_apply(),
+                    (_, KeyCode::Char('d')) => app.show_plan(),
 
                     _ => {}
                 }
@@ -0,0 +1,389 @@
+//! Fallback palettes derived from per-app terminal configs
+//!
+//! Some Omarchy themes ship only `kitty.conf`, `ghostty.conf`,
+//! `alacritty.toml` or `hyprland.conf` instead of `colors.toml`. These
+//! parsers lift the colors out of those files so every installed theme
+//! still gets swatches and generated configs.
+
+use crate::color::{ColorError, Rgba};
+use crate::theme::ColorPalette;
+use std::path::Path;
+
+/// Per-app files we know how to read, in order of preference
+pub const PALETTE_SOURCES: &[&str] = &["kitty.conf", "ghostty.conf", "alacritty.toml"];
+
+/// A colors.toml entry that couldn't be stored in a [`ColorPalette`]
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum FieldError {
+    #[error("unknown key `{0}`")]
+    UnknownKey(String),
+    #[error("`{field}`: {source}")]
+    InvalidColor { field: String, source: ColorError },
+}
+
+impl ColorPalette {
+    /// Mutable slot for ANSI color `i` (0-15)
+    fn ansi_slot(&mut self, i: usize) -> Option<&mut Option<Rgba>> {
+        Some(match i {
+            0 => &mut self.color0,
+            1 => &mut self.color1,
+            2 => &mut self.color2,
+            3 => &mut self.color3,
+            4 => &mut self.color4,
+            5 => &mut self.color5,
+            6 => &mut self.color6,
+            7 => &mut self.color7,
+            8 => &mut self.color8,
+            9 => &mut self.color9,
+            10 => &mut self.color10,
+            11 => &mut self.color11,
+            12 => &mut self.color12,
+            13 => &mut self.color13,
+            14 => &mut self.color14,
+            15 => &mut self.color15,
+            _ => return None,
+        })
+    }
+
+    /// Mutable slot for a colors.toml key; None for unknown keys
+    pub fn field_mut(&mut self, key: &str) -> Option<&mut Option<Rgba>> {
+        match key {
+            "foreground" => Some(&mut self.foreground),
+            "background" => Some(&mut self.background),
+            "accent" => Some(&mut self.accent),
+            "cursor" => Some(&mut self.cursor),
+            "selection_background" => Some(&mut self.selection_background),
+            "selection_foreground" => Some(&mut self.selection_foreground),
+            _ => {
+                let i: usize = key.strip_prefix("color")?.parse().ok()?;
+                if key != format!("color{}", i) {
+                    return None;
+                }
+                self.ansi_slot(i)
+            }
+        }
+    }
+
+    /// Parse `value` into the field named `key`
+    pub fn set_field(&mut self, key: &str, value: &str) -> Result<(), FieldError> {
+        let slot = self
+            .field_mut(key)
+            .ok_or_else(|| FieldError::UnknownKey(key.to_string()))?;
+        let color = Rgba::parse(value).map_err(|source| FieldError::InvalidColor {
+            field: key.to_string(),
+            source,
+        })?;
+        *slot = Some(color);
+        Ok(())
+    }
+
+    fn set_ansi(&mut self, i: usize, color: Option<Rgba>) {
+        if let Some(slot) = self.ansi_slot(i) {
+            *slot = color;
+        }
+    }
+
+    /// Fill every unset field from `other`
+    fn fill_from(&mut self, mut other: ColorPalette) {
+        fn fill(dst: &mut Option<Rgba>, src: Option<Rgba>) {
+            if dst.is_none() {
+                *dst = src;
+            }
+        }
+        fill(&mut self.foreground, other.foreground.take());
+        fill(&mut self.background, other.background.take());
+        fill(&mut self.accent, other.accent.take());
+        fill(&mut self.cursor, other.cursor.take());
+        fill(&mut self.selection_background, other.selection_background.take());
+        fill(&mut self.selection_foreground, other.selection_foreground.take());
+        for i in 0..16 {
+            let src = other.ansi_slot(i).and_then(|s| s.take());
+            if let Some(dst) = self.ansi_slot(i) {
+                fill(dst, src);
+            }
+        }
+    }
+
+    /// `(key, value)` pairs in colors.toml naming, for exporting as `THEME_*`
+    pub fn color_vars(&self) -> Vec<(String, String)> {
+        let named = [
+            ("foreground", &self.foreground),
+            ("background", &self.background),
+            ("accent", &self.accent),
+            ("cursor", &self.cursor),
+            ("selection_background", &self.selection_background),
+            ("selection_foreground", &self.selection_foreground),
+        ];
+        let mut vars: Vec<(String, String)> = named
+            .into_iter()
+            .filter_map(|(k, v)| Some((k.to_string(), v.as_ref()?.to_hex())))
+            .collect();
+        let mut ansi = self.clone();
+        for i in 0..16 {
+            if let Some(v) = ansi.ansi_slot(i).and_then(|s| s.take()) {
+                vars.push((format!("color{}", i), v.to_hex()));
+            }
+        }
+        vars
+    }
+
+    /// No field set at all
+    fn is_empty(&self) -> bool {
+        *self == ColorPalette::default()
+    }
+}
+
+/// Build a palette from whichever per-app configs the theme ships.
+///
+/// Returns the palette and the file it primarily came from. Later sources
+/// only fill gaps left by earlier ones; hyprland.conf contributes the accent.
+pub fn derive_palette(theme_dir: &Path) -> Option<(ColorPalette, &'static str)> {
+    let mut result: Option<(ColorPalette, &'static str)> = None;
+
+    for &file in PALETTE_SOURCES {
+        let Ok(content) = std::fs::read_to_string(theme_dir.join(file)) else {
+            continue;
+        };
+        let parsed = match file {
+            "kitty.conf" => parse_kitty(&content),
+            "ghostty.conf" => parse_ghostty(&content),
+            _ => parse_alacritty(&content),
+        };
+        if parsed.is_empty() {
+            continue;
+        }
+        match &mut result {
+            None => result = Some((parsed, file)),
+            Some((palette, _)) => palette.fill_from(parsed),
+        }
+    }
+
+    let (mut palette, source) = result?;
+    if palette.accent.is_none() {
+        palette.accent = std::fs::read_to_string(theme_dir.join("hyprland.conf"))
+            .ok()
+            .and_then(|c| parse_hyprland_accent(&c))
+            .or(palette.color4);
+    }
+    Some((palette, source))
+}
+
+/// kitty: `key value` lines
+pub fn parse_kitty(content: &str) -> ColorPalette {
+    let mut p = ColorPalette::default();
+    for line in content.lines() {
+        let line = line.trim();
+        if line.starts_with('#') {
+            continue;
+        }
+        let mut parts = line.split_whitespace();
+        let (Some(key), Some(value)) = (parts.next(), parts.next()) else {
+            continue;
+        };
+        match key {
+            "foreground" => p.foreground = normalize(value),
+            "background" => p.background = normalize(value),
+            "cursor" => p.cursor = normalize(value),
+            "selection_foreground" => p.selection_foreground = normalize(value),
+            "selection_background" => p.selection_background = normalize(value),
+            "active_border_color" => p.accent = normalize(value),
+            _ => {
+                if let Some(i) = key.strip_prefix("color").and_then(|n| n.parse().ok()) {
+                    p.set_ansi(i, normalize(value));
+                }
+            }
+        }
+    }
+    p
+}
+
+/// ghostty: `key = value`, ANSI colors as `palette = N=#rrggbb`
+pub fn parse_ghostty(content: &str) -> ColorPalette {
+    let mut p = ColorPalette::default();
+    for line in content.lines() {
+        let line = line.trim();
+        if line.starts_with('#') {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            "foreground" => p.foreground = normalize(value),
+            "background" => p.background = normalize(value),
+            "cursor-color" => p.cursor = normalize(value),
+            "selection-foreground" => p.selection_foreground = normalize(value),
+            "selection-background" => p.selection_background = normalize(value),
+            "palette" => {
+                if let Some((idx, color)) = value.split_once('=') {
+                    if let Ok(i) = idx.trim().parse() {
+                        p.set_ansi(i, normalize(color));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    p
+}
+
+/// alacritty.toml: `[colors.primary]`, `[colors.normal]`, `[colors.bright]`, ...
+pub fn parse_alacritty(content: &str) -> ColorPalette {
+    let mut p = ColorPalette::default();
+    let Ok(doc) = content.parse::<toml::Table>() else {
+        return p;
+    };
+    let Some(colors) = doc.get("colors").and_then(|c| c.as_table()) else {
+        return p;
+    };
+    let get = |section: &str, key: &str| {
+        colors
+            .get(section)
+            .and_then(|s| s.get(key))
+            .and_then(|v| v.as_str())
+            .and_then(normalize)
+    };
+
+    p.foreground = get("primary", "foreground");
+    p.background = get("primary", "background");
+    p.cursor = get("cursor", "cursor");
+    p.selection_foreground = get("selection", "text");
+    p.selection_background = get("selection", "background");
+
+    const NAMES: [&str; 8] = ["black", "red", "green", "yellow", "blue", "magenta", "cyan", "white"];
+    for (i, name) in NAMES.iter().enumerate() {
+        p.set_ansi(i, get("normal", name));
+        p.set_ansi(i + 8, get("bright", name));
+    }
+    p
+}
+
+/// Accent from `col.active_border = rgb(xxxxxx)` / `rgba(xxxxxxxx)` /
+/// `0xAARRGGBB` / `$activeBorderColor = ...`
+pub fn parse_hyprland_accent(content: &str) -> Option<Rgba> {
+    content.lines().find_map(|line| {
+        let (key, value) = line.split_once('=')?;
+        let key = key.trim();
+        if key != "col.active_border" && !key.eq_ignore_ascii_case("$activeBorderColor") {
+            return None;
+        }
+        // Gradients list several colors (and maybe an angle); the first
+        // color is the accent
+        gradient_tokens(value).into_iter().find_map(hyprland_color)
+    })
+}
+
+/// Split a gradient on whitespace, keeping `rgb(26, 27, 38)` in one piece
+fn gradient_tokens(value: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut depth = 0;
+    let mut start = None;
+    for (i, c) in value.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            c if c.is_whitespace() && depth == 0 => {
+                if let Some(s) = start.take() {
+                    tokens.push(&value[s..i]);
+                }
+                continue;
+            }
+            _ => {}
+        }
+        start.get_or_insert(i);
+    }
+    if let Some(s) = start {
+        tokens.push(&value[s..]);
+    }
+    tokens
+}
+
+/// One gradient color; Hyprland's `0x` form puts the alpha first
+fn hyprland_color(token: &str) -> Option<Rgba> {
+    if let Some(hex) = token.strip_prefix("0x").or_else(|| token.strip_prefix("0X")) {
+        if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        let rgb = if hex.len() == 8 { &hex[2..] } else { hex };
+        return Rgba::parse(&format!("#{}", rgb)).ok();
+    }
+    if !token.starts_with("rgb") {
+        return None;
+    }
+    Rgba::parse(token).ok().map(Rgba::opaque)
+}
+
+/// Terminal configs also allow bare `rrggbb`
+fn normalize(value: &str) -> Option<Rgba> {
+    let v = value.trim().trim_matches(|c| c == '"' || c == '\'');
+    Rgba::parse(v)
+        .or_else(|_| Rgba::parse(&format!("#{}", v)))
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kitty_colors() {
+        let p = parse_kitty(
+            "# Tokyo Night\nforeground #c0caf5\nbackground   #1A1B26\ncolor0 #15161e\ncolor15 #c0caf5\ncolor16 #ff0000\n",
+        );
+        assert_eq!(p.foreground, Some(Rgba::hex(0xc0caf5)));
+        assert_eq!(p.background, Some(Rgba::hex(0x1a1b26)));
+        assert_eq!(p.color0, Some(Rgba::hex(0x15161e)));
+        assert_eq!(p.color15, Some(Rgba::hex(0xc0caf5)));
+    }
+
+    #[test]
+    fn ghostty_palette_entries() {
+        let p = parse_ghostty("background = 1a1b26\ncursor-color = #c0caf5\npalette = 1=#f7768e\npalette = 12 = #7aa2f7\n");
+        assert_eq!(p.background, Some(Rgba::hex(0x1a1b26)));
+        assert_eq!(p.cursor, Some(Rgba::hex(0xc0caf5)));
+        assert_eq!(p.color1, Some(Rgba::hex(0xf7768e)));
+        assert_eq!(p.color12, Some(Rgba::hex(0x7aa2f7)));
+    }
+
+    #[test]
+    fn any_color_makes_a_palette() {
+        assert!(ColorPalette::default().is_empty());
+        assert!(!parse_kitty("color9 #ff7a93\n").is_empty());
+        let accent_only = ColorPalette {
+            accent: Some(Rgba::hex(0x7aa2f7)),
+            ..Default::default()
+        };
+        assert!(!accent_only.is_empty());
+    }
+
+    #[test]
+    fn alacritty_sections() {
+        let p = parse_alacritty(
+            "[colors.primary]\nbackground = '0x1a1b26'\nforeground = \"#c0caf5\"\n\n[colors.normal]\nred = '#f7768e'\n\n[colors.bright]\nblue = '#7aa2f7'\n",
+        );
+        assert_eq!(p.background, Some(Rgba::hex(0x1a1b26)));
+        assert_eq!(p.color1, Some(Rgba::hex(0xf7768e)));
+        assert_eq!(p.color12, Some(Rgba::hex(0x7aa2f7)));
+    }
+
+    #[test]
+    fn hyprland_accent() {
+        let conf = "general {\n    col.active_border = rgb(8A8A8D)\n}\n";
+        assert_eq!(parse_hyprland_accent(conf), Some(Rgba::hex(0x8a8a8d)));
+        assert_eq!(
+            parse_hyprland_accent("$activeBorderColor = rgba(33ccffee)\n"),
+            Some(Rgba::hex(0x33ccff))
+        );
+        assert_eq!(
+            parse_hyprland_accent("col.active_border = rgb(26, 27, 38) rgba(7aa2f7ee) 45deg\n"),
+            Some(Rgba::hex(0x1a1b26))
+        );
+        assert_eq!(
+            parse_hyprland_accent("col.active_border = 0xee33ccff 0xee00ff99 45deg\n"),
+            Some(Rgba::hex(0x33ccff))
+        );
+        assert_eq!(parse_hyprland_accent("col.active_border = $activeBorderColor\n"), None);
+        assert_eq!(parse_hyprland_accent("col.active_border = 0x3é3ccff\n"), None);
+    }
+}
@@ -0,0 +1,410 @@
+//! Dry-run planning: what an apply would touch, without touching it
+
+use crate::apply::find_hooks;
+use crate::theme;
+use anyhow::{bail, Context, Result};
+use std::path::{Path, PathBuf};
+
+/// One side effect an apply would have
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlanAction {
+    /// `link` becomes a symlink to `target`
+    Symlink { link: PathBuf, target: PathBuf },
+    /// File is (re)written from the palette
+    Generate(PathBuf),
+    /// File is copied over
+    Copy { from: PathBuf, to: PathBuf },
+    /// Existing file is edited in place
+    Edit(PathBuf),
+    /// File is deleted
+    Remove(PathBuf),
+    /// Running process is signalled or reloaded
+    Signal { process: String, how: String, running: bool },
+    /// Running process is killed and started again
+    Restart { process: String, running: bool },
+}
+
+impl PlanAction {
+    pub fn describe(&self) -> String {
+        match self {
+            PlanAction::Symlink { link, target } => {
+                format!("link     {} -> {}", tilde(link), tilde(target))
+            }
+            PlanAction::Generate(path) => format!("write    {}", tilde(path)),
+            PlanAction::Copy { from, to } => format!("copy     {} -> {}", tilde(from), tilde(to)),
+            PlanAction::Edit(path) => format!("edit     {}", tilde(path)),
+            PlanAction::Remove(path) => format!("remove   {}", tilde(path)),
+            PlanAction::Signal { process, how, running } => {
+                format!("signal   {} ({}){}", process, how, not_running(*running))
+            }
+            PlanAction::Restart { process, running } => {
+                format!("restart  {}{}", process, not_running(*running))
+            }
+        }
+    }
+
+    /// Does this action disturb a live process?
+    pub fn is_disruptive(&self) -> bool {
+        matches!(
+            self,
+            PlanAction::Signal { running: true, .. } | PlanAction::Restart { running: true, .. }
+        )
+    }
+}
+
+fn not_running(running: bool) -> &'static str {
+    if running {
+        ""
+    } else {
+        " — not running, skipped"
+    }
+}
+
+/// Planned effects of one hook
+#[derive(Debug, Clone)]
+pub struct HookPlan {
+    pub name: String,
+    /// False for hooks we don't ship; their effects can't be predicted
+    pub known: bool,
+    pub actions: Vec<PlanAction>,
+}
+
+/// Everything `apply` would do for a theme
+#[derive(Debug, Clone)]
+pub struct ApplyPlan {
+    pub theme: String,
+    /// Core switch: `current` symlink and `.current-theme`
+    pub core: Vec<PlanAction>,
+    pub hooks: Vec<HookPlan>,
+}
+
+impl ApplyPlan {
+    pub fn disruptive_count(&self) -> usize {
+        self.hooks
+            .iter()
+            .flat_map(|h| &h.actions)
+            .filter(|a| a.is_disruptive())
+            .count()
+    }
+}
+
+/// Build the plan for applying `theme_name`
+pub fn plan_apply(cruzalex_dir: &Path, theme_name: &str) -> Result<ApplyPlan> {
+    theme::check_name(theme_name)?;
+    let theme_dir = cruzalex_dir.join("themes").join(theme_name);
+    if !theme_dir.is_dir() {
+        bail!("Theme not found: {}", theme_name);
+    }
+    let home = dirs::home_dir().context("Could not find home directory")?;
+
+    let core = vec![
+        PlanAction::Symlink {
+            link: cruzalex_dir.join("current"),
+            target: theme_dir.clone(),
+        },
+        PlanAction::Generate(cruzalex_dir.join(".current-theme")),
+    ];
+
+    let ctx = PlanContext {
+        theme_dir: &theme_dir,
+        cruzalex_dir,
+        config: home.join(".config"),
+        home: &home,
+    };
+
+    let hooks = find_hooks(&cruzalex_dir.join("hooks/theme-set.d"))
+        .into_iter()
+        .map(|hook| {
+            let name = hook
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            match ctx.hook_actions(&name) {
+                Some(actions) => HookPlan { name, known: true, actions },
+                None => HookPlan { name, known: false, actions: Vec::new() },
+            }
+        })
+        .collect();
+
+    Ok(ApplyPlan {
+        theme: theme_name.to_string(),
+        core,
+        hooks,
+    })
+}
+
+struct PlanContext<'a> {
+    theme_dir: &'a Path,
+    cruzalex_dir: &'a Path,
+    config: PathBuf,
+    home: &'a Path,
+}
+
+impl PlanContext<'_> {
+    fn has(&self, rel: &str) -> bool {
+        self.theme_dir.join(rel).exists()
+    }
+
+    fn link(&self, link: PathBuf, rel: &str) -> PlanAction {
+        PlanAction::Symlink {
+            link,
+            target: self.theme_dir.join(rel),
+        }
+    }
+
+    /// Mirrors what each stock hook in `cruzalex/hooks/theme-set.d` does
+    fn hook_actions(&self, hook: &str) -> Option<Vec<PlanAction>> {
+        let cfg = &self.config;
+        let has_colors = self.has("colors.toml");
+        let mut actions = Vec::new();
+
+        match hook {
+            "10-terminals.sh" => {
+                if self.has("ghostty.conf") {
+                    actions.push(self.link(cfg.join("ghostty/theme"), "ghostty.conf"));
+                }
+                if self.has("kitty.conf") {
+                    actions.push(self.link(cfg.join("kitty/theme.conf"), "kitty.conf"));
+                    actions.push(signal("kitty", "kitty @ set-colors / SIGUSR1"));
+                }
+            }
+            "20-hyprland.sh" => {
+                // The hook bails out entirely when Hyprland isn't running
+                if process_running("Hyprland") {
+                    if self.has("hyprland.conf") {
+                        actions.push(self.link(cfg.join("hypr/theme.conf"), "hyprland.conf"));
+                    }
+                    actions.push(signal("Hyprland", "hyprctl reload + border colors"));
+                }
+            }
+            "20-waybar.sh" => {
+                if self.has("waybar/style.css") {
+                    actions.push(self.link(cfg.join("waybar/theme.css"), "waybar/style.css"));
+                } else if self.has("waybar.css") {
+                    actions.push(self.link(cfg.join("waybar/theme.css"), "waybar.css"));
+                }
+                if has_colors {
+                    actions.push(PlanAction::Generate(cfg.join("waybar/colors.css")));
+                }
+                actions.push(restart("waybar"));
+            }
+            "25-starship.sh" => {
+                let starship = cfg.join("starship.toml");
+                if starship.exists() {
+                    actions.push(PlanAction::Edit(starship));
+                }
+            }
+            "30-btop.sh" => {
+                let dest = cfg.join("btop/themes/cruzalex.theme");
+                for rel in ["btop.theme", "btop/theme.theme"] {
+                    if self.has(rel) {
+                        actions.push(PlanAction::Copy {
+                            from: self.theme_dir.join(rel),
+                            to: dest,
+                        });
+                        break;
+                    }
+                }
+                let conf = cfg.join("btop/btop.conf");
+                if conf.exists() {
+                    actions.push(PlanAction::Edit(conf));
+                }
+            }
+            "30-mako.sh" => {
+                let conf = cfg.join("mako/config");
+                if conf.exists() {
+                    actions.push(PlanAction::Edit(conf));
+                }
+                actions.push(signal("mako", "makoctl reload"));
+            }
+            "40-neovim.sh" => {
+                let plugin = cfg.join("nvim/lua/plugins/cruzalex-theme.lua");
+                if self.has("neovim.lua") {
+                    actions.push(self.link(plugin, "neovim.lua"));
+                } else if plugin.symlink_metadata().is_ok() {
+                    actions.push(PlanAction::Remove(plugin));
+                }
+                actions.push(PlanAction::Generate(self.cruzalex_dir.join(".nvim-colorscheme")));
+                actions.push(PlanAction::Generate(cfg.join("nvim/lua/cruzalex/theme.lua")));
+                actions.push(signal("nvim", "SIGUSR1"));
+            }
+            "50-ncspot.sh" => {
+                actions.push(PlanAction::Generate(cfg.join("ncspot/theme.toml")));
+                let flatpak = self.home.join(".var/app/io.github.hrkfdn.ncspot/config/ncspot");
+                if flatpak.is_dir() {
+                    actions.push(PlanAction::Generate(flatpak.join("theme.toml")));
+                }
+            }
+            "50-spotify.sh" => {
+                let dest = cfg.join("spotify-player/theme.toml");
+                if self.has("spotify-player/theme.toml") {
+                    actions.push(self.link(dest, "spotify-player/theme.toml"));
+                } else if has_colors {
+                    actions.push(PlanAction::Generate(dest));
+                }
+            }
+            "50-tmux.sh" => {
+                actions.push(PlanAction::Generate(cfg.join("tmux/theme.conf")));
+                actions.push(signal("tmux", "source-file"));
+            }
+            "50-yazi.sh" => {
+                let dest = cfg.join("yazi/theme.toml");
+                if self.has("yazi") {
+                    if self.has("yazi/theme.toml") {
+                        actions.push(self.link(dest, "yazi/theme.toml"));
+                    }
+                } else if has_colors {
+                    actions.push(PlanAction::Generate(dest));
+                }
+            }
+            "60-wallpaper.sh" => {
+                actions.push(PlanAction::Generate(self.cruzalex_dir.join(".current-wallpaper")));
+                actions.push(PlanAction::Generate(self.cruzalex_dir.join(".wallpaper-index")));
+                actions.push(restart("swaybg"));
+            }
+            _ => return None,
+        }
+
+        Some(actions)
+    }
+}
+
+fn signal(process: &str, how: &str) -> PlanAction {
+    PlanAction::Signal {
+        process: process.to_string(),
+        how: how.to_string(),
+        running: process_running(process),
+    }
+}
+
+fn restart(process: &str) -> PlanAction {
+    PlanAction::Restart {
+        process: process.to_string(),
+        running: process_running(process),
+    }
+}
+
+/// Equivalent of `pgrep -x <name>`: match against /proc/*/comm
+fn process_running(name: &str) -> bool {
+    let Ok(entries) = std::fs::read_dir("/proc") else {
+        return false;
+    };
+    entries.filter_map(|e| e.ok()).any(|e| {
+        std::fs::read_to_string(e.path().join("comm"))
+            .map(|comm| comm.trim_end() == name)
+            .unwrap_or(false)
+    })
+}
+
+/// Shorten paths under $HOME to `~/...` for display
+fn tilde(path: &Path) -> String {
+    if let Some(home) = dirs::home_dir() {
+        if let Ok(rest) = path.strip_prefix(&home) {
+            return format!("~/{}", rest.display());
+        }
+    }
+    path.display().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    const STOCK_HOOKS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../../cruzalex/hooks/theme-set.d");
+
+    /// A CRUZALEX_DIR with one theme holding `files` and (empty) executable hooks
+    fn cruzalex_dir(files: &[&str], hooks: &[&str]) -> tempfile::TempDir {
+        let tmp = tempfile::tempdir().unwrap();
+        let theme = tmp.path().join("themes/night");
+        std::fs::create_dir_all(&theme).unwrap();
+        for file in files {
+            std::fs::write(theme.join(file), "").unwrap();
+        }
+        let hooks_dir = tmp.path().join("hooks/theme-set.d");
+        std::fs::create_dir_all(&hooks_dir).unwrap();
+        for hook in hooks {
+            let path = hooks_dir.join(hook);
+            std::fs::write(&path, "#!/bin/sh\n").unwrap();
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+        tmp
+    }
+
+    #[test]
+    fn plans_links_and_generated_files() {
+        let tmp = cruzalex_dir(
+            &["ghostty.conf", "kitty.conf", "colors.toml"],
+            &["10-terminals.sh", "50-tmux.sh", "90-mine.sh"],
+        );
+        let dir = tmp.path();
+        let theme = dir.join("themes/night");
+        let cfg = dirs::home_dir().unwrap().join(".config");
+
+        let plan = plan_apply(dir, "night").unwrap();
+        assert_eq!(plan.theme, "night");
+        assert_eq!(
+            plan.core[0],
+            PlanAction::Symlink { link: dir.join("current"), target: theme.clone() }
+        );
+
+        let names: Vec<&str> = plan.hooks.iter().map(|h| h.name.as_str()).collect();
+        assert_eq!(names, ["10-terminals.sh", "50-tmux.sh", "90-mine.sh"]);
+
+        let terminals = &plan.hooks[0].actions;
+        assert_eq!(
+            terminals[..2],
+            [
+                PlanAction::Symlink {
+                    link: cfg.join("ghostty/theme"),
+                    target: theme.join("ghostty.conf"),
+                },
+                PlanAction::Symlink {
+                    link: cfg.join("kitty/theme.conf"),
+                    target: theme.join("kitty.conf"),
+                },
+            ]
+        );
+        assert!(matches!(&terminals[2], PlanAction::Signal { process, .. } if process == "kitty"));
+        assert_eq!(plan.hooks[1].actions[0], PlanAction::Generate(cfg.join("tmux/theme.conf")));
+
+        // Custom hooks are listed, with nothing predicted
+        assert!(!plan.hooks[2].known);
+        assert!(plan.hooks[2].actions.is_empty());
+    }
+
+    #[test]
+    fn only_plans_what_the_theme_ships() {
+        let tmp = cruzalex_dir(&["ghostty.conf"], &["10-terminals.sh", "50-yazi.sh"]);
+        let plan = plan_apply(tmp.path(), "night").unwrap();
+        // No kitty.conf: no kitty link or reload
+        assert_eq!(plan.hooks[0].actions.len(), 1);
+        // No colors.toml: nothing for yazi to generate
+        assert!(plan.hooks[1].actions.is_empty());
+        assert!(plan_apply(tmp.path(), "dawn").is_err());
+    }
+
+    /// `hook_actions` mirrors the stock hooks by hand; a new hook must be
+    /// added there too
+    #[test]
+    fn knows_every_stock_hook() {
+        let tmp = cruzalex_dir(&["colors.toml"], &[]);
+        let home = tmp.path().join("home");
+        let ctx = PlanContext {
+            theme_dir: &tmp.path().join("themes/night"),
+            cruzalex_dir: tmp.path(),
+            config: home.join(".config"),
+            home: &home,
+        };
+        let stock = find_hooks(Path::new(STOCK_HOOKS));
+        assert!(!stock.is_empty(), "no hooks found in {}", STOCK_HOOKS);
+        for hook in stock {
+            let name = hook.file_name().unwrap().to_string_lossy().to_string();
+            assert!(
+                ctx.hook_actions(&name).is_some(),
+                "{} is not described in plan::hook_actions",
+                name
+            );
+        }
+    }
+}
@@ -0,0 +1,494 @@
+//! `/` search queries
+//!
+//! Whitespace-separated terms, all of which must match (AND). Any term can be
+//! negated with a leading `-`, and double quotes group words:
+//!
+//! - free text: fuzzy subsequence match against the theme name (`tknight`
+//!   finds `tokyo-night`); negated text (`-night`) excludes by substring
+//! - `name:<text>`, `author:<text>`: case-insensitive substring
+//! - `stars:50`, `stars:>50`, `stars:<=10`, `stars:10..100`
+//! - `is:light|dark|fav|installed|available|active|readable`
+//! - `has:backgrounds|preview|palette|warnings`
+//! - `<slot>:<color>[~<distance>]`, e.g. `bg:#1a1b26~10` or `accent:pink`.
+//!   Hex colors match within a CIEDE2000 distance (default
+//!   [`DEFAULT_DISTANCE`]); names match a hue bucket.
+//!
+//! Tokens that don't parse as a term are treated as free text.
+
+use crate::color::Rgba;
+use crate::fuzzy::{fuzzy_match, FuzzyMatch};
+use crate::theme::{ColorPalette, Theme, ThemeStatus};
+
+/// ΔE2000 used when a hex term has no `~distance`
+pub const DEFAULT_DISTANCE: f64 = 10.0;
+
+/// Which palette color a term looks at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Slot {
+    Background,
+    Foreground,
+    Accent,
+    Cursor,
+    Selection,
+    Ansi(usize),
+    /// Any color in the palette
+    Any,
+}
+
+impl Slot {
+    fn parse(s: &str) -> Option<Self> {
+        Some(match s {
+            "bg" | "background" => Slot::Background,
+            "fg" | "foreground" => Slot::Foreground,
+            "accent" => Slot::Accent,
+            "cursor" => Slot::Cursor,
+            "sel" | "selection" => Slot::Selection,
+            "color" | "any" => Slot::Any,
+            _ => {
+                let i: usize = s.strip_prefix("color")?.parse().ok()?;
+                if i > 15 {
+                    return None;
+                }
+                Slot::Ansi(i)
+            }
+        })
+    }
+
+    fn colors(self, p: &ColorPalette) -> Vec<Rgba> {
+        let ansi = [
+            p.color0, p.color1, p.color2, p.color3, p.color4, p.color5, p.color6, p.color7,
+            p.color8, p.color9, p.color10, p.color11, p.color12, p.color13, p.color14, p.color15,
+        ];
+        let picked: Vec<Option<Rgba>> = match self {
+            Slot::Background => vec![p.background],
+            Slot::Foreground => vec![p.foreground],
+            Slot::Accent => vec![p.accent],
+            Slot::Cursor => vec![p.cursor],
+            Slot::Selection => vec![p.selection_background],
+            Slot::Ansi(i) => vec![ansi[i]],
+            Slot::Any => [p.background, p.foreground, p.accent]
+                .into_iter()
+                .chain(ansi)
+                .collect(),
+        };
+        picked.into_iter().flatten().collect()
+    }
+}
+
+/// Named color families for `accent:pink` style terms
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hue {
+    Red,
+    Orange,
+    Yellow,
+    Green,
+    Cyan,
+    Blue,
+    Purple,
+    Pink,
+    Gray,
+    Black,
+    White,
+}
+
+impl Hue {
+    fn parse(s: &str) -> Option<Self> {
+        Some(match s {
+            "red" => Hue::Red,
+            "orange" => Hue::Orange,
+            "yellow" => Hue::Yellow,
+            "green" => Hue::Green,
+            "cyan" | "teal" => Hue::Cyan,
+            "blue" => Hue::Blue,
+            "purple" | "violet" => Hue::Purple,
+            "pink" | "magenta" => Hue::Pink,
+            "gray" | "grey" => Hue::Gray,
+            "black" => Hue::Black,
+            "white" => Hue::White,
+            _ => return None,
+        })
+    }
+
+    /// Bucket a color by HSV: near-greys first, then hue ranges
+    pub fn of(color: Rgba) -> Self {
+        let (h, s, v) = color.to_hsv();
+        if v < 0.2 {
+            return Hue::Black;
+        }
+        if s < 0.2 {
+            return if v > 0.85 { Hue::White } else { Hue::Gray };
+        }
+        match h {
+            h if !(15.0..345.0).contains(&h) => Hue::Red,
+            h if h < 45.0 => Hue::Orange,
+            h if h < 70.0 => Hue::Yellow,
+            h if h < 165.0 => Hue::Green,
+            h if h < 195.0 => Hue::Cyan,
+            h if h < 255.0 => Hue::Blue,
+            h if h < 290.0 => Hue::Purple,
+            _ => Hue::Pink,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColorMatch {
+    /// Within this ΔE2000 of a color
+    Near(Rgba, f64),
+    InHue(Hue),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColorTerm {
+    pub slot: Slot,
+    pub target: ColorMatch,
+}
+
+impl ColorTerm {
+    /// `slot:color[~distance]`; None if the token isn't a color term
+    pub fn parse(token: &str) -> Option<Self> {
+        let (slot, value) = token.split_once(':')?;
+        let slot = Slot::parse(&slot.to_lowercase())?;
+        let value = value.to_lowercase();
+
+        if let Some(hue) = Hue::parse(&value) {
+            return Some(Self { slot, target: ColorMatch::InHue(hue) });
+        }
+        let (color, distance) = match value.split_once('~') {
+            Some((color, d)) => (color, d.parse().ok()?),
+            None => (value.as_str(), DEFAULT_DISTANCE),
+        };
+        let color = Rgba::parse(color).ok()?;
+        Some(Self { slot, target: ColorMatch::Near(color, distance) })
+    }
+
+    pub fn matches(&self, palette: &ColorPalette) -> bool {
+        self.slot.colors(palette).into_iter().any(|c| match self.target {
+            ColorMatch::Near(target, max) => c.to_lab().delta_e2000(target.to_lab()) <= max,
+            ColorMatch::InHue(hue) => Hue::of(c) == hue,
+        })
+    }
+}
+
+/// Comparison in a `stars:` term
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StarsCmp {
+    Eq(u32),
+    Gt(u32),
+    Ge(u32),
+    Lt(u32),
+    Le(u32),
+    /// Inclusive range `a..b`
+    Range(u32, u32),
+}
+
+impl StarsCmp {
+    fn parse(s: &str) -> Option<Self> {
+        Some(if let Some((lo, hi)) = s.split_once("..") {
+            StarsCmp::Range(lo.parse().ok()?, hi.parse().ok()?)
+        } else if let Some(n) = s.strip_prefix(">=") {
+            StarsCmp::Ge(n.parse().ok()?)
+        } else if let Some(n) = s.strip_prefix("<=") {
+            StarsCmp::Le(n.parse().ok()?)
+        } else if let Some(n) = s.strip_prefix('>') {
+            StarsCmp::Gt(n.parse().ok()?)
+        } else if let Some(n) = s.strip_prefix('<') {
+            StarsCmp::Lt(n.parse().ok()?)
+        } else {
+            StarsCmp::Eq(s.parse().ok()?)
+        })
+    }
+
+    fn matches(self, stars: u32) -> bool {
+        match self {
+            StarsCmp::Eq(n) => stars == n,
+            StarsCmp::Gt(n) => stars > n,
+            StarsCmp::Ge(n) => stars >= n,
+            StarsCmp::Lt(n) => stars < n,
+            StarsCmp::Le(n) => stars <= n,
+            StarsCmp::Range(lo, hi) => (lo..=hi).contains(&stars),
+        }
+    }
+}
+
+/// `is:` flags
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flag {
+    Light,
+    Dark,
+    Favorite,
+    /// Installed or active
+    Installed,
+    Available,
+    Active,
+    Readable,
+}
+
+/// `has:` features
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Feature {
+    Backgrounds,
+    Preview,
+    Palette,
+    Warnings,
+}
+
+/// One search term
+#[derive(Debug, Clone, PartialEq)]
+pub enum Term {
+    /// Lowercased free text
+    Text(String),
+    Name(String),
+    Author(String),
+    Stars(StarsCmp),
+    Is(Flag),
+    Has(Feature),
+    Color(ColorTerm),
+}
+
+impl Term {
+    fn parse(token: &str) -> Self {
+        let keyed = token.split_once(':').and_then(|(key, value)| {
+            let value_lc = value.to_lowercase();
+            Some(match key.to_lowercase().as_str() {
+                "name" => Term::Name(value_lc),
+                "author" => Term::Author(value_lc),
+                "stars" => Term::Stars(StarsCmp::parse(value)?),
+                "is" => Term::Is(match value_lc.as_str() {
+                    "light" => Flag::Light,
+                    "dark" => Flag::Dark,
+                    "fav" | "favorite" | "favourite" => Flag::Favorite,
+                    "installed" => Flag::Installed,
+                    "available" => Flag::Available,
+                    "active" | "current" => Flag::Active,
+                    "readable" => Flag::Readable,
+                    _ => return None,
+                }),
+                "has" => Term::Has(match value_lc.as_str() {
+                    "backgrounds" | "bg" | "wallpapers" => Feature::Backgrounds,
+                    "preview" => Feature::Preview,
+                    "palette" | "colors" => Feature::Palette,
+                    "warnings" | "diagnostics" => Feature::Warnings,
+                    _ => return None,
+                }),
+                _ => Term::Color(ColorTerm::parse(token)?),
+            })
+        });
+        keyed.unwrap_or_else(|| Term::Text(token.to_lowercase()))
+    }
+
+    fn matches(&self, theme: &Theme, favorite: bool) -> bool {
+        match self {
+            Term::Text(text) => text_match(text, theme).is_some(),
+            Term::Name(text) => theme.name.to_lowercase().contains(text),
+            Term::Author(text) => theme
+                .author
+                .as_ref()
+                .is_some_and(|a| a.to_lowercase().contains(text)),
+            Term::Stars(cmp) => theme.stars.is_some_and(|s| cmp.matches(s)),
+            Term::Is(flag) => match flag {
+                Flag::Light => theme.is_light == Some(true),
+                Flag::Dark => theme.is_light == Some(false),
+                Flag::Favorite => favorite,
+                Flag::Installed => {
+                    matches!(theme.status, ThemeStatus::Active | ThemeStatus::Installed)
+                }
+                Flag::Available => theme.status == ThemeStatus::Available,
+                Flag::Active => theme.status == ThemeStatus::Active,
+                Flag::Readable => theme.contrast.as_ref().is_some_and(|c| c.passes()),
+            },
+            Term::Has(feature) => match feature {
+                Feature::Backgrounds => theme.background_count > 0,
+                Feature::Preview => theme.preview_path.is_some() || theme.preview_url.is_some(),
+                Feature::Palette => theme.colors.is_some(),
+                Feature::Warnings => !theme.diagnostics.is_empty(),
+            },
+            Term::Color(term) => theme.colors.as_ref().is_some_and(|p| term.matches(p)),
+        }
+    }
+}
+
+/// Best fuzzy match of free text against the display name or the name
+fn text_match(text: &str, theme: &Theme) -> Option<FuzzyMatch> {
+    let display = fuzzy_match(text, &theme.display_name);
+    let name = fuzzy_match(text, &theme.name);
+    match (display, name) {
+        (Some(d), Some(n)) if n.score > d.score => Some(n),
+        (Some(d), _) => Some(d),
+        (None, n) => n,
+    }
+}
+
+/// A term, possibly negated with `-`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Clause {
+    pub negated: bool,
+    pub term: Term,
+}
+
+/// A parsed `/` query
+#[derive(Debug, Clone, Default)]
+pub struct Query {
+    pub clauses: Vec<Clause>,
+}
+
+impl Query {
+    pub fn parse(input: &str) -> Self {
+        let clauses = tokenize(input)
+            .into_iter()
+            .map(|token| match token.strip_prefix('-') {
+                Some(rest) if !rest.is_empty() => Clause { negated: true, term: Term::parse(rest) },
+                _ => Clause { negated: false, term: Term::parse(&token) },
+            })
+            .collect();
+        Self { clauses }
+    }
+
+    /// Does `theme` satisfy every clause? `favorite` feeds `is:fav`
+    pub fn matches(&self, theme: &Theme, favorite: bool) -> bool {
+        self.clauses.iter().all(|c| match (&c.term, c.negated) {
+            // Excluding every fuzzy match would hide far too much
+            (Term::Text(text), true) => {
+                !theme.name.to_lowercase().contains(text)
+                    && !theme.display_name.to_lowercase().contains(text)
+            }
+            (term, negated) => term.matches(theme, favorite) != negated,
+        })
+    }
+
+    /// Free-text terms that must match
+    fn texts(&self) -> impl Iterator<Item = &str> {
+        self.clauses.iter().filter_map(|c| match &c.term {
+            Term::Text(text) if !c.negated => Some(text.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Whether results should be ranked by [`Query::score`]
+    pub fn has_text(&self) -> bool {
+        self.texts().next().is_some()
+    }
+
+    /// Summed fuzzy score of the free-text terms; higher is better
+    pub fn score(&self, theme: &Theme) -> i64 {
+        self.texts()
+            .filter_map(|text| text_match(text, theme))
+            .map(|m| m.score)
+            .sum()
+    }
+
+    /// Char indices of `display_name` to highlight
+    pub fn highlights(&self, display_name: &str) -> Vec<usize> {
+        let mut positions: Vec<usize> = self
+            .texts()
+            .filter_map(|text| fuzzy_match(text, display_name))
+            .flat_map(|m| m.positions)
+            .collect();
+        positions.sort_unstable();
+        positions.dedup();
+        positions
+    }
+}
+
+/// Split on whitespace, keeping double-quoted runs together (quotes dropped)
+fn tokenize(input: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    for c in input.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokyo() -> ColorPalette {
+        ColorPalette {
+            background: Some(Rgba::hex(0x1a1b26)),
+            foreground: Some(Rgba::hex(0xc0caf5)),
+            accent: Some(Rgba::hex(0xff79c6)),
+            color4: Some(Rgba::hex(0x7aa2f7)),
+            ..Default::default()
+        }
+    }
+
+    fn theme(name: &str) -> Theme {
+        let mut theme = Theme::from_remote(name, "https://github.com/someone/omarchy-x-theme", Some("bjarneo"));
+        theme.stars = Some(120);
+        theme
+    }
+
+    fn terms(input: &str) -> Vec<Term> {
+        Query::parse(input).clauses.into_iter().map(|c| c.term).collect()
+    }
+
+    #[test]
+    fn parses_terms_and_free_text() {
+        assert_eq!(
+            terms("Night bg:#1a1b26~5 accent:pink"),
+            [
+                Term::Text("night".to_string()),
+                Term::Color(ColorTerm { slot: Slot::Background, target: ColorMatch::Near(Rgba::hex(0x1a1b26), 5.0) }),
+                Term::Color(ColorTerm { slot: Slot::Accent, target: ColorMatch::InHue(Hue::Pink) }),
+            ]
+        );
+        assert_eq!(
+            terms("stars:>50 stars:10..20 is:fav has:backgrounds author:\"Bjarne O\""),
+            [
+                Term::Stars(StarsCmp::Gt(50)),
+                Term::Stars(StarsCmp::Range(10, 20)),
+                Term::Is(Flag::Favorite),
+                Term::Has(Feature::Backgrounds),
+                Term::Author("bjarne o".to_string()),
+            ]
+        );
+        // Not a known term: stays free text
+        assert_eq!(terms("foo:bar is:shiny"), [Term::Text("foo:bar".to_string()), Term::Text("is:shiny".to_string())]);
+    }
+
+    #[test]
+    fn clauses_are_anded_and_negatable() {
+        let t = theme("tokyo-night");
+        assert!(Query::parse("tokyo author:bjarne stars:>50").matches(&t, false));
+        assert!(!Query::parse("tokyo stars:>500").matches(&t, false));
+        assert!(Query::parse("-is:installed is:available").matches(&t, false));
+        assert!(!Query::parse("is:fav").matches(&t, false));
+        assert!(Query::parse("is:fav").matches(&t, true));
+        assert!(Query::parse("-has:backgrounds").matches(&t, false));
+        assert!(!Query::parse("-tokyo").matches(&t, false));
+        // Fuzzy for positive text, substring for negated
+        assert!(Query::parse("tknight").matches(&t, false));
+        assert!(Query::parse("-tknight").matches(&t, false));
+        // No palette and no hint in the name: neither light nor dark
+        let unknown = theme("kanso");
+        assert!(!Query::parse("is:dark").matches(&unknown, false));
+        assert!(!Query::parse("is:light").matches(&unknown, false));
+        assert!(Query::parse("is:dark").matches(&t, false));
+    }
+
+    #[test]
+    fn near_and_hue_matching() {
+        let p = tokyo();
+        assert!(ColorTerm::parse("bg:#1c1d28").unwrap().matches(&p));
+        assert!(!ColorTerm::parse("bg:#1c1d28~0.5").unwrap().matches(&p));
+        assert!(ColorTerm::parse("accent:pink").unwrap().matches(&p));
+        assert!(!ColorTerm::parse("accent:green").unwrap().matches(&p));
+        assert!(ColorTerm::parse("color4:blue").unwrap().matches(&p));
+        assert!(ColorTerm::parse("any:blue").unwrap().matches(&p));
+        assert!(ColorTerm::parse("bg:black").unwrap().matches(&p));
+    }
+}
@@ -0,0 +1,98 @@
+//! "Find similar themes": rank palettes by perceptual distance
+
+use crate::color::Rgba;
+use crate::theme::{ColorPalette, Theme};
+
+/// Matches shown in the side panel (one per digit key)
+pub const MAX_MATCHES: usize = 9;
+
+/// Compared colors and their weights; background and foreground dominate
+/// how a theme feels, so they count more than any single ANSI color
+fn weighted_slots(p: &ColorPalette) -> [(Option<Rgba>, f64); 19] {
+    [
+        (p.background, 4.0),
+        (p.foreground, 2.0),
+        (p.accent, 2.0),
+        (p.color0, 1.0),
+        (p.color1, 1.0),
+        (p.color2, 1.0),
+        (p.color3, 1.0),
+        (p.color4, 1.0),
+        (p.color5, 1.0),
+        (p.color6, 1.0),
+        (p.color7, 1.0),
+        (p.color8, 1.0),
+        (p.color9, 1.0),
+        (p.color10, 1.0),
+        (p.color11, 1.0),
+        (p.color12, 1.0),
+        (p.color13, 1.0),
+        (p.color14, 1.0),
+        (p.color15, 1.0),
+    ]
+}
+
+/// Weighted mean CIEDE2000 over the colors both palettes define.
+///
+/// None unless both have a background, which anchors the comparison.
+pub fn palette_distance(a: &ColorPalette, b: &ColorPalette) -> Option<f64> {
+    a.background?;
+    b.background?;
+
+    let (mut total, mut weight) = (0.0, 0.0);
+    for ((ca, w), (cb, _)) in weighted_slots(a).into_iter().zip(weighted_slots(b)) {
+        if let (Some(ca), Some(cb)) = (ca, cb) {
+            total += w * ca.to_lab().delta_e2000(cb.to_lab());
+            weight += w;
+        }
+    }
+    Some(total / weight)
+}
+
+/// The `limit` themes closest to `themes[target]`, as (index, distance)
+pub fn rank_similar(themes: &[Theme], target: usize, limit: usize) -> Vec<(usize, f64)> {
+    let Some(reference) = themes.get(target).and_then(|t| t.colors.as_ref()) else {
+        return Vec::new();
+    };
+
+    let mut ranked: Vec<(usize, f64)> = themes
+        .iter()
+        .enumerate()
+        .filter(|&(i, _)| i != target)
+        .filter_map(|(i, theme)| Some((i, palette_distance(reference, theme.colors.as_ref()?)?)))
+        .collect();
+    ranked.sort_by(|a, b| a.1.total_cmp(&b.1));
+    ranked.truncate(limit);
+    ranked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn palette(bg: u32, fg: u32, accent: u32) -> ColorPalette {
+        ColorPalette {
+            background: Some(Rgba::hex(bg)),
+            foreground: Some(Rgba::hex(fg)),
+            accent: Some(Rgba::hex(accent)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn identical_palettes_have_zero_distance() {
+        let p = palette(0x1a1b26, 0xc0caf5, 0x7aa2f7);
+        assert_eq!(palette_distance(&p, &p), Some(0.0));
+    }
+
+    #[test]
+    fn nearer_palette_ranks_first() {
+        let tokyo = palette(0x1a1b26, 0xc0caf5, 0x7aa2f7);
+        let storm = palette(0x24283b, 0xc0caf5, 0x7aa2f7);
+        let latte = palette(0xeff1f5, 0x4c4f69, 0x8839ef);
+        let far = palette_distance(&tokyo, &latte).unwrap();
+        let near = palette_distance(&tokyo, &storm).unwrap();
+        assert!(near < far, "{} vs {}", near, far);
+        assert!(palette_distance(&tokyo, &ColorPalette::default()).is_none());
+    }
+}
@@ -0,0 +1,480 @@
+//! Where the remote catalogue comes from
+//!
+//! Sources are listed in `~/.config/cruzalex/themes.toml`; without any, the
+//! curated awesome-omarchy list and the GitHub topic searches are used:
+//!
+//! ```toml
+//! [[source]]
+//! type = "curated"                    # the awesome-omarchy README
+//!
+//! [[source]]
+//! type = "github-topic"               # default searches without `topic`
+//! topic = "omarchy-theme"
+//! name_filter = "omarchy"             # optional
+//!
+//! [[source]]
+//! type = "index"                      # JSON or TOML list of themes
+//! url = "https://intranet.example.com/themes.json"
+//!
+//! [[source]]
+//! type = "static"
+//! themes = [{ name = "nord", url = "https://github.com/x/omarchy-nord-theme" }]
+//!
+//! [[source]]
+//! type = "local"                      # a directory of theme clones
+//! path = "~/src/themes"
+//!
+//! [[source]]
+//! type = "git-org"                    # every matching repo of a user/org
+//! org = "bjarneo"
+//! name_filter = "omarchy"             # default "theme"
+//! ```
+//!
+//! An index is a JSON array, `{"themes": [...]}`, or TOML `[[theme]]`
+//! tables; each entry has `name`, `url` and optional `author` and
+//! `description`.
+
+use crate::awesome;
+use crate::config::Config;
+use crate::github::{self, Fetched};
+use crate::theme::{
+    self, fetch_github_api_themes, get_awesome_omarchy_themes, GitHubRepo, Theme, ThemeStatus,
+    TopicSearch, DEFAULT_SEARCH_CAP,
+};
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+
+pub type SourceFuture<'a> = Pin<Box<dyn Future<Output = Result<Listing>> + Send + 'a>>;
+
+/// What a source found
+#[derive(Debug, Default)]
+pub struct Listing {
+    pub themes: Vec<Theme>,
+    /// Parts of the source that failed, when the list is incomplete
+    pub failures: Vec<String>,
+}
+
+impl From<Vec<Theme>> for Listing {
+    fn from(themes: Vec<Theme>) -> Self {
+        Self {
+            themes,
+            failures: Vec::new(),
+        }
+    }
+}
+
+/// Something that lists remote themes
+pub trait ThemeSource: Send + Sync {
+    /// Short description for status messages and the About screen
+    fn label(&self) -> String;
+
+    fn fetch(&self) -> SourceFuture<'_>;
+}
+
+/// One `[[source]]` table
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case", deny_unknown_fields)]
+pub enum SourceConfig {
+    Curated,
+    Static {
+        themes: Vec<IndexEntry>,
+    },
+    GithubTopic {
+        topic: Option<String>,
+        name_filter: Option<String>,
+        cap: Option<usize>,
+    },
+    Index {
+        url: String,
+    },
+    Local {
+        path: String,
+    },
+    GitOrg {
+        org: String,
+        name_filter: Option<String>,
+    },
+}
+
+impl SourceConfig {
+    pub fn build(self, search_cap: usize) -> Box<dyn ThemeSource> {
+        match self {
+            SourceConfig::Curated => Box::new(Curated),
+            SourceConfig::Static { themes } => Box::new(StaticList { themes }),
+            SourceConfig::GithubTopic {
+                topic,
+                name_filter,
+                cap,
+            } => Box::new(GitHubTopics {
+                searches: match &topic {
+                    Some(topic) => vec![TopicSearch::topic(topic, name_filter.as_deref())],
+                    None => TopicSearch::defaults(),
+                },
+                label: topic
+                    .map_or_else(|| "GitHub topics".to_string(), |t| format!("topic:{}", t)),
+                cap: cap.unwrap_or(search_cap),
+            }),
+            SourceConfig::Index { url } => Box::new(Index { url }),
+            SourceConfig::Local { path } => Box::new(LocalClones {
+                path: expand_home(&path),
+            }),
+            SourceConfig::GitOrg { org, name_filter } => Box::new(GitOrg {
+                org,
+                name_filter: name_filter
+                    .unwrap_or_else(|| "theme".to_string())
+                    .to_lowercase(),
+            }),
+        }
+    }
+}
+
+/// The sources from themes.toml, or the defaults when it lists none
+pub fn configured() -> Result<Vec<Box<dyn ThemeSource>>> {
+    let config = Config::load()?;
+    let search_cap = config.search_cap.unwrap_or(DEFAULT_SEARCH_CAP);
+    let sources = if config.sources.is_empty() {
+        vec![
+            SourceConfig::Curated,
+            SourceConfig::GithubTopic {
+                topic: None,
+                name_filter: None,
+                cap: None,
+            },
+        ]
+    } else {
+        config.sources
+    };
+    Ok(sources.into_iter().map(|s| s.build(search_cap)).collect())
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// A theme listed in an index or a static source
+#[derive(Debug, Clone, Deserialize)]
+pub struct IndexEntry {
+    pub name: String,
+    pub url: String,
+    #[serde(default)]
+    pub author: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+impl IndexEntry {
+    fn to_theme(&self) -> Theme {
+        let mut theme = Theme::from_remote(&self.name, &self.url, self.author.as_deref());
+        theme.description = self.description.clone();
+        theme
+    }
+}
+
+/// Parse an index document: JSON array, `{"themes": [...]}`, or TOML `[[theme]]`
+pub fn parse_index(content: &str) -> Result<Vec<IndexEntry>> {
+    #[derive(Deserialize)]
+    struct JsonIndex {
+        themes: Vec<IndexEntry>,
+    }
+    #[derive(Deserialize)]
+    struct TomlIndex {
+        #[serde(default)]
+        theme: Vec<IndexEntry>,
+    }
+
+    let trimmed = content.trim_start();
+    let entries: Vec<IndexEntry> = if trimmed.starts_with('[') && !trimmed.starts_with("[[") {
+        serde_json::from_str(content).context("invalid JSON index")?
+    } else if trimmed.starts_with('{') {
+        serde_json::from_str::<JsonIndex>(content)
+            .context("invalid JSON index")?
+            .themes
+    } else {
+        toml::from_str::<TomlIndex>(content)
+            .context("invalid TOML index")?
+            .theme
+    };
+    // Names become directories under themes/ when installed
+    for entry in &entries {
+        theme::check_name(&entry.name)?;
+    }
+    Ok(entries)
+}
+
+/// GET a text document with the usual timeout and user agent
+async fn fetch_text(url: &str) -> Result<String> {
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(15))
+        .build()?;
+    Ok(client
+        .get(url)
+        .header("User-Agent", github::USER_AGENT)
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?)
+}
+
+/// The awesome-omarchy README, parsed live
+struct Curated;
+
+impl ThemeSource for Curated {
+    fn label(&self) -> String {
+        "awesome-omarchy".to_string()
+    }
+
+    fn fetch(&self) -> SourceFuture<'_> {
+        Box::pin(async {
+            // Unreachable: the embedded snapshot. A README that no longer
+            // parses is an error instead, so the breakage gets noticed.
+            let Ok(readme) = fetch_text(awesome::README_URL).await else {
+                return Ok(get_awesome_omarchy_themes().into());
+            };
+            let themes = awesome::parse_readme(&readme);
+            if themes.is_empty() {
+                bail!("no themes found in the awesome-omarchy README");
+            }
+            Ok(themes.into())
+        })
+    }
+}
+
+/// Themes listed inline in themes.toml
+struct StaticList {
+    themes: Vec<IndexEntry>,
+}
+
+impl ThemeSource for StaticList {
+    fn label(&self) -> String {
+        "static list".to_string()
+    }
+
+    fn fetch(&self) -> SourceFuture<'_> {
+        Box::pin(async {
+            for entry in &self.themes {
+                theme::check_name(&entry.name)?;
+            }
+            let themes: Vec<Theme> = self.themes.iter().map(IndexEntry::to_theme).collect();
+            Ok(themes.into())
+        })
+    }
+}
+
+/// GitHub repository searches by topic
+struct GitHubTopics {
+    searches: Vec<TopicSearch>,
+    label: String,
+    cap: usize,
+}
+
+impl ThemeSource for GitHubTopics {
+    fn label(&self) -> String {
+        self.label.clone()
+    }
+
+    fn fetch(&self) -> SourceFuture<'_> {
+        Box::pin(async {
+            let (themes, failures) = fetch_github_api_themes(&self.searches, self.cap).await?;
+            Ok(Listing { themes, failures })
+        })
+    }
+}
+
+/// A JSON/TOML index at a URL or local path
+struct Index {
+    url: String,
+}
+
+impl ThemeSource for Index {
+    fn label(&self) -> String {
+        format!("index {}", self.url)
+    }
+
+    fn fetch(&self) -> SourceFuture<'_> {
+        Box::pin(async {
+            let content = if self.url.starts_with("http://") || self.url.starts_with("https://") {
+                fetch_text(&self.url).await?
+            } else {
+                let path = expand_home(self.url.trim_start_matches("file://"));
+                std::fs::read_to_string(&path)
+                    .with_context(|| format!("reading {}", path.display()))?
+            };
+            let themes: Vec<Theme> = parse_index(&content)?
+                .iter()
+                .map(IndexEntry::to_theme)
+                .collect();
+            Ok(themes.into())
+        })
+    }
+}
+
+/// A directory of theme clones; installing one clones from the local path
+struct LocalClones {
+    path: PathBuf,
+}
+
+impl ThemeSource for LocalClones {
+    fn label(&self) -> String {
+        format!("local {}", self.path.display())
+    }
+
+    fn fetch(&self) -> SourceFuture<'_> {
+        let dir = self.path.clone();
+        Box::pin(async move {
+            let themes = tokio::task::spawn_blocking(move || read_clones(&dir)).await??;
+            Ok(themes.into())
+        })
+    }
+}
+
+/// The git clones in `dir`, read like installed themes so palette and
+/// preview show right away, then presented as available from the clone
+fn read_clones(dir: &Path) -> Result<Vec<Theme>> {
+    let entries = std::fs::read_dir(dir).with_context(|| format!("reading {}", dir.display()))?;
+    let mut themes = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.join(".git").exists() {
+            continue;
+        }
+        let Ok(mut theme) = Theme::from_local(path.clone(), None) else {
+            continue;
+        };
+        theme.status = ThemeStatus::Available;
+        theme.local_path = None;
+        theme.remote_url = Some(path.display().to_string());
+        themes.push(theme);
+    }
+    Ok(themes)
+}
+
+/// Every repo of a GitHub user or organization whose name matches
+struct GitOrg {
+    org: String,
+    name_filter: String,
+}
+
+impl ThemeSource for GitOrg {
+    fn label(&self) -> String {
+        format!("github.com/{}", self.org)
+    }
+
+    fn fetch(&self) -> SourceFuture<'_> {
+        Box::pin(async {
+            let client = reqwest::Client::builder()
+                .timeout(std::time::Duration::from_secs(15))
+                .build()?;
+            let url = format!("https://api.github.com/users/{}/repos", self.org);
+            let mut themes = Vec::new();
+            for page in 1.. {
+                let page = page.to_string();
+                let (result, _) = github::get_json::<Vec<GitHubRepo>>(
+                    &client,
+                    &url,
+                    &[("per_page", "100"), ("page", &page)],
+                    None,
+                )
+                .await;
+                let Fetched::Modified(repos, _) = result? else {
+                    bail!("unexpected 304 listing {}", self.org);
+                };
+                let last_page = repos.len() < 100;
+                themes.extend(
+                    repos
+                        .iter()
+                        .filter(|r| r.name.to_lowercase().contains(&self.name_filter))
+                        .map(Theme::from_github),
+                );
+                if last_page {
+                    break;
+                }
+            }
+            Ok(themes.into())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_every_index_format() {
+        let json = r#"[{"name": "nord", "url": "https://github.com/a/nord"}]"#;
+        let wrapped =
+            r#"{"themes": [{"name": "nord", "url": "https://github.com/a/nord", "author": "a"}]}"#;
+        let toml = "[[theme]]\nname = \"nord\"\nurl = \"https://github.com/a/nord\"\ndescription = \"Arctic\"\n";
+        for content in [json, wrapped, toml] {
+            let entries = parse_index(content).unwrap();
+            assert_eq!(entries.len(), 1, "{}", content);
+            assert_eq!(entries[0].name, "nord");
+        }
+        assert!(parse_index("{\"themes\": 3}").is_err());
+
+        // Names end up as directories under themes/
+        for name in ["../../.local/share/x", "a/b", "a\\\\b", ".hidden", "current", ""] {
+            let json = format!(r#"[{{"name": "{}", "url": "https://github.com/a/x"}}]"#, name);
+            let err = parse_index(&json).unwrap_err();
+            assert!(err.to_string().contains("invalid theme name"), "{}: {}", name, err);
+        }
+    }
+
+    #[tokio::test]
+    async fn lists_local_clones_as_available() {
+        let dir = tempfile::tempdir().unwrap();
+        let clone = dir.path().join("nord");
+        std::fs::create_dir_all(clone.join(".git")).unwrap();
+        std::fs::write(clone.join("colors.toml"), "background = \"#2e3440\"\n").unwrap();
+        std::fs::create_dir(dir.path().join("not-a-clone")).unwrap();
+
+        let source = SourceConfig::Local {
+            path: dir.path().display().to_string(),
+        }
+        .build(100);
+        let listing = source.fetch().await.unwrap();
+        assert_eq!(listing.themes.len(), 1);
+        let theme = &listing.themes[0];
+        assert_eq!(theme.name, "nord");
+        assert_eq!(theme.status, ThemeStatus::Available);
+        assert_eq!(theme.remote_url.as_deref(), Some(clone.to_str().unwrap()));
+        assert!(theme.colors.is_some());
+    }
+
+    #[test]
+    fn reads_source_tables() {
+        let config: Config = toml::from_str(
+            r#"
+            [[source]]
+            type = "index"
+            url = "https://example.com/themes.toml"
+
+            [[source]]
+            type = "git-org"
+            org = "bjarneo"
+            "#,
+        )
+        .unwrap();
+        let labels: Vec<String> = config
+            .sources
+            .into_iter()
+            .map(|s| s.build(100).label())
+            .collect();
+        assert_eq!(
+            labels,
+            [
+                "index https://example.com/themes.toml",
+                "github.com/bjarneo"
+            ]
+        );
+
+        let bad = toml::from_str::<Config>("[[source]]\ntype = \"ftp\"\n");
+        assert!(bad.is_err());
+    }
+}
@@ -1,8 +1,16 @@
 //! Theme data structures and loading
 
-use anyhow::{Context, Result};
+use crate::contrast::{self, ContrastReport};
+use crate::diagnostics::{self, Diagnostic, ParseMode, Severity};
+use crate::forge::Repo;
+use crate::color::Rgba;
+use crate::github::{self, Fetched, RepoMeta};
+use crate::palette;
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 
 /// Theme status
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -34,30 +42,30 @@ impl ThemeStatus {
 }
 
 /// Color palette from colors.toml
-#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, Default)]
 pub struct ColorPalette {
-    pub foreground: Option<String>,
-    pub background: Option<String>,
-    pub accent: Option<String>,
-    pub cursor: Option<String>,
-    pub selection_background: Option<String>,
-    pub selection_foreground: Option<String>,
-    pub color0: Option<String>,
-    pub color1: Option<String>,
-    pub color2: Option<String>,
-    pub color3: Option<String>,
-    pub color4: Option<String>,
-    pub color5: Option<String>,
-    pub color6: Option<String>,
-    pub color7: Option<String>,
-    pub color8: Option<String>,
-    pub color9: Option<String>,
-    pub color10: Option<String>,
-    pub color11: Option<String>,
-    pub color12: Option<String>,
-    pub color13: Option<String>,
-    pub color14: Option<String>,
-    pub color15: Option<String>,
+    pub foreground: Option<Rgba>,
+    pub background: Option<Rgba>,
+    pub accent: Option<Rgba>,
+    pub cursor: Option<Rgba>,
+    pub selection_background: Option<Rgba>,
+    pub selection_foreground: Option<Rgba>,
+    pub color0: Option<Rgba>,
+    pub color1: Option<Rgba>,
+    pub color2: Option<Rgba>,
+    pub color3: Option<Rgba>,
+    pub color4: Option<Rgba>,
+    pub color5: Option<Rgba>,
+    pub color6: Option<Rgba>,
+    pub color7: Option<Rgba>,
+    pub color8: Option<Rgba>,
+    pub color9: Option<Rgba>,
+    pub color10: Option<Rgba>,
+    pub color11: Option<Rgba>,
+    pub color12: Option<Rgba>,
+    pub color13: Option<Rgba>,
+    pub color14: Option<Rgba>,
+    pub color15: Option<Rgba>,
 }
 
 /// Theme metadata
@@ -79,14 +87,31 @@ pub struct Theme {
     pub preview_url: Option<String>,
     /// Color palette
     pub colors: Option<ColorPalette>,
-    /// Is this a light theme?
-    pub is_light: bool,
+    /// Per-app config the palette was derived from, when colors.toml was unusable
+    pub palette_source: Option<&'static str>,
+    /// Problems found in colors.toml
+    pub diagnostics: Vec<Diagnostic>,
+    /// WCAG contrast audit of the palette
+    pub contrast: Option<ContrastReport>,
+    /// Light (`Some(true)`) or dark (`Some(false)`); `None` until a palette
+    /// or marker tells, and then neither the Light nor the Dark filter shows it
+    pub is_light: Option<bool>,
     /// Number of backgrounds available
     pub background_count: usize,
     /// Author/source
     pub author: Option<String>,
     /// GitHub stars (if from GitHub)
     pub stars: Option<u32>,
+    /// Repository description (if from GitHub)
+    pub description: Option<String>,
+    /// Default branch of the repo
+    pub default_branch: Option<String>,
+    /// Last push to the repo
+    pub pushed_at: Option<DateTime<Utc>>,
+    /// GitHub topics
+    pub topics: Vec<String>,
+    /// SPDX license id (or name)
+    pub license: Option<String>,
 }
 
 impl Theme {
@@ -107,17 +132,30 @@ impl Theme {
         };
 
         let colors_path = path.join("colors.toml");
-        let colors = if colors_path.exists() {
-            let content = std::fs::read_to_string(&colors_path)?;
-            // Try to parse, but don't fail the whole theme if TOML is invalid
-            // Some themes have malformed colors.toml (e.g., duplicate keys)
-            toml::from_str(&content).ok()
+        let (colors, diagnostics) = if colors_path.exists() {
+            match std::fs::read_to_string(&colors_path) {
+                Ok(content) => diagnostics::load_colors(&content, ParseMode::default()),
+                Err(e) => (
+                    None,
+                    vec![Diagnostic::error(None, format!("could not read colors.toml: {}", e))],
+                ),
+            }
         } else {
-            None
+            (None, Vec::new())
         };
+        // Fall back to the terminal configs for themes without a usable colors.toml
+        let (colors, palette_source) = match colors {
+            Some(colors) => (Some(colors), None),
+            None => match palette::derive_palette(&path) {
+                Some((colors, source)) => (Some(colors), Some(source)),
+                None => (None, None),
+            },
+        };
+
+        let contrast = colors.as_ref().and_then(contrast::audit);
 
         let preview_path = find_preview_image(&path);
-        let is_light = path.join("light.mode").exists();
+        let is_light = classify_light(&path, colors.as_ref().and_then(|c| c.background));
         let background_count = count_backgrounds(&path);
 
         Ok(Self {
@@ -129,17 +167,49 @@ impl Theme {
             preview_path,
             preview_url: None,
             colors,
+            palette_source,
+            diagnostics,
+            contrast,
             is_light,
             background_count,
             author: None,
             stars: None,
+            description: None,
+            default_branch: None,
+            pushed_at: None,
+            topics: Vec::new(),
+            license: None,
         })
     }
 
+    /// Attach a palette fetched for a theme that isn't installed yet
+    pub fn set_remote_palette(&mut self, colors: ColorPalette) {
+        self.contrast = contrast::audit(&colors);
+        if let Some(bg) = colors.background {
+            self.is_light = Some(is_light_background(bg));
+        }
+        self.colors = Some(colors);
+    }
+
+    /// Attach metadata fetched from the GitHub API
+    pub fn set_repo_meta(&mut self, meta: &RepoMeta) {
+        self.stars = Some(meta.stars);
+        self.description = meta.description.clone();
+        self.default_branch = meta.default_branch.clone();
+        self.pushed_at = meta.pushed_at;
+        self.topics = meta.topics.clone();
+        self.license = meta.license.clone();
+    }
+
+    /// Most severe diagnostic, if any
+    pub fn worst_diagnostic(&self) -> Option<Severity> {
+        self.diagnostics.iter().map(|d| d.severity).max()
+    }
+
     /// Create a theme from remote data (name and URL)
     pub fn from_remote(name: &str, url: &str, author: Option<&str>) -> Self {
         let display_name = format_theme_name(name);
-        let preview_url = github_clone_url_to_preview_url(url);
+        let preview_url = default_preview_url(url);
 
         Self {
             name: name.to_string(),
@@ -150,10 +220,18 @@ impl Theme {
             preview_path: None,
             preview_url,
             colors: None,
-            is_light: name.contains("light"),
+            palette_source: None,
+            diagnostics: Vec::new(),
+            contrast: None,
+            is_light: light_from_name(name),
             background_count: 0,
             author: author.map(|s| s.to_string()),
             stars: None,
+            description: None,
+            default_branch: None,
+            pushed_at: None,
+            topics: Vec::new(),
+            license: None,
         }
     }
 
@@ -167,7 +245,8 @@ impl Theme {
             .to_string();
 
         let display_name = format_theme_name(&name);
-        let preview_url = github_clone_url_to_preview_url(&repo.clone_url);
+        let preview_url = default_preview_url(&repo.clone_url);
+        let is_light = light_from_name(&name);
 
         Self {
             name,
@@ -178,44 +257,36 @@ impl Theme {
             preview_path: None,
             preview_url,
             colors: None,
-            is_light: false,
+            palette_source: None,
+            diagnostics: Vec::new(),
+            contrast: None,
+            is_light,
             background_count: 0,
             author: Some(repo.owner.login.clone()),
             stars: Some(repo.stargazers_count),
+            description: repo.description.clone(),
+            default_branch: repo.default_branch.clone(),
+            pushed_at: repo.pushed_at,
+            topics: repo.topics.clone(),
+            license: repo.license.as_ref().map(|l| l.spdx_id.clone().unwrap_or_else(|| l.name.clone())),
         }
     }
 }
 
-/// Convert GitHub clone URL to a list of candidate raw preview image URLs.
+/// Candidate raw preview image URLs for a repo on any supported forge.
 ///
 /// Theme repos use wildly different conventions: `preview.png`, `preview.jpg`,
 /// `preview-1.png` (akane), `theme.png` (dracula), `screenshot.*`, sometimes
 /// under `assets/` or `images/`. We try a fan-out of common names across main
 /// and master branches; the downloader picks the first one that returns real
 /// image bytes.
-fn github_clone_url_to_preview_url(clone_url: &str) -> Option<String> {
+fn default_preview_url(clone_url: &str) -> Option<String> {
     let urls = preview_candidate_urls(clone_url);
     urls.into_iter().next()
 }
 
-pub fn preview_candidate_urls(github_url: &str) -> Vec<String> {
-    let url = github_url.trim_end_matches(".git").trim_end_matches('/');
-
-    let path = if let Some(idx) = url.find("raw.githubusercontent.com/") {
-        let rest = &url[idx + "raw.githubusercontent.com/".len()..];
-        let parts: Vec<&str> = rest.splitn(3, '/').collect();
-        if parts.len() < 2 {
-            return vec![];
-        }
-        format!("{}/{}", parts[0], parts[1])
-    } else if let Some(idx) = url.find("github.com/") {
-        let rest = &url[idx + "github.com/".len()..];
-        let parts: Vec<&str> = rest.splitn(3, '/').collect();
-        if parts.len() < 2 {
-            return vec![];
-        }
-        format!("{}/{}", parts[0], parts[1])
-    } else {
+pub fn preview_candidate_urls(repo_url: &str) -> Vec<String> {
+    let Some(repo) = Repo::parse(repo_url) else {
         return vec![];
     };
 
@@ -236,16 +307,15 @@ pub fn preview_candidate_urls(github_url: &str) -> Vec<String> {
     let mut urls = Vec::with_capacity(FILENAMES.len() * BRANCHES.len());
     for branch in BRANCHES {
         for fname in FILENAMES {
-            urls.push(format!(
-                "https://raw.githubusercontent.com/{}/{}/{}",
-                path, branch, fname
-            ));
+            urls.extend(repo.raw_url(branch, fname));
         }
     }
     urls
 }
 
 /// GitHub repository data (for fallback API search)
+// Mirrors the API response; not every field is consumed yet.
+#[allow(dead_code)]
 #[derive(Debug, Deserialize)]
 pub struct GitHubRepo {
     pub name: String,
@@ -255,6 +325,20 @@ pub struct GitHubRepo {
     pub description: Option<String>,
     pub stargazers_count: u32,
     pub owner: GitHubOwner,
+    #[serde(default)]
+    pub default_branch: Option<String>,
+    #[serde(default)]
+    pub pushed_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub topics: Vec<String>,
+    #[serde(default)]
+    pub license: Option<GitHubLicense>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GitHubLicense {
+    pub name: String,
+    pub spdx_id: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -283,7 +367,7 @@ fn format_theme_name(name: &str) -> String {
 }
 
 /// Find preview image in theme directory
-fn find_preview_image(path: &PathBuf) -> Option<PathBuf> {
+fn find_preview_image(path: &Path) -> Option<PathBuf> {
     const NAMES: &[&str] = &[
         "preview.png", "preview.jpg", "preview.jpeg", "preview.webp",
         "theme.png", "theme.jpg",
@@ -333,7 +417,7 @@ fn find_preview_image(path: &PathBuf) -> Option<PathBuf> {
 }
 
 /// Count background images in theme
-fn count_backgrounds(path: &PathBuf) -> usize {
+fn count_backgrounds(path: &Path) -> usize {
     let bg_dir = path.join("backgrounds");
     if !bg_dir.exists() {
         return 0;
@@ -357,8 +441,65 @@ fn count_backgrounds(path: &PathBuf) -> usize {
         .unwrap_or(0)
 }
 
+/// Luminance above which black text beats white text on this background
+/// (the point where both contrast ratios are equal)
+const LIGHT_LUMINANCE_THRESHOLD: f64 = 0.179;
+
+fn is_light_background(background: Rgba) -> bool {
+    background.relative_luminance() > LIGHT_LUMINANCE_THRESHOLD
+}
+
+/// A `light.mode` or `dark.mode` marker decides; without one, go by the
+/// background color, and without that it's unknown
+fn classify_light(theme_dir: &Path, background: Option<Rgba>) -> Option<bool> {
+    if theme_dir.join("light.mode").exists() {
+        return Some(true);
+    }
+    if theme_dir.join("dark.mode").exists() {
+        return Some(false);
+    }
+    background.map(is_light_background)
+}
+
+/// No palette yet: guess from the name, word by word so "twilight" doesn't
+/// count as light
+fn light_from_name(name: &str) -> Option<bool> {
+    name.split(['-', '_']).find_map(|w| match w.to_lowercase().as_str() {
+        "light" | "day" | "dawn" => Some(true),
+        "dark" | "night" => Some(false),
+        _ => None,
+    })
+}
+
+/// Files any one of which makes a directory a theme. Some Omarchy themes use
+/// per-app configs instead of a centralized colors.toml.
+pub const THEME_FILES: &[&str] = &[
+    "colors.toml",
+    "ghostty.conf",
+    "kitty.conf",
+    "alacritty.toml",
+    "hyprland.conf",
+];
+
+pub fn is_theme_dir(path: &Path) -> bool {
+    THEME_FILES.iter().any(|file| path.join(file).is_file())
+}
+
+/// Check a name is a single directory under themes/ (not `current`, the
+/// symlink, or a hidden staging directory)
+pub fn check_name(name: &str) -> Result<()> {
+    if name.is_empty()
+        || name.starts_with('.')
+        || name.contains(['/', '\\'])
+        || name == "current"
+    {
+        bail!("invalid theme name '{}'", name);
+    }
+    Ok(())
+}
+
 /// Load all local themes
-pub fn load_local_themes(themes_dir: &PathBuf, current_theme: Option<&str>) -> Result<Vec<Theme>> {
+pub fn load_local_themes(themes_dir: &Path, current_theme: Option<&str>) -> Result<Vec<Theme>> {
     let mut themes = Vec::new();
 
     if !themes_dir.exists() {
@@ -374,22 +515,15 @@ pub fn load_local_themes(themes_dir: &PathBuf, current_theme: Option<&str>) -> R
             continue;
         }
 
-        // Accept themes with colors.toml OR any app config files
-        // Some Omarchy themes use per-app configs instead of centralized colors.toml
-        let has_colors = path.join("colors.toml").exists();
-        let has_ghostty = path.join("ghostty.conf").exists();
-        let has_kitty = path.join("kitty.conf").exists();
-        let has_alacritty = path.join("alacritty.toml").exists();
-        let has_hyprland = path.join("hyprland.conf").exists();
-
-        if !has_colors && !has_ghostty && !has_kitty && !has_alacritty && !has_hyprland {
+        if !is_theme_dir(&path) {
             continue;
         }
 
         match Theme::from_local(path, current_theme) {
             Ok(theme) => themes.push(theme),
             Err(_) => {
-                // Silently skip themes with invalid configs (e.g., TOML parse errors)
+                // Only an unreadable directory name gets here; colors.toml
+                // problems are recorded as diagnostics on the theme instead.
                 // Don't use eprintln as it corrupts the TUI
                 continue;
             }
@@ -400,7 +534,8 @@ pub fn load_local_themes(themes_dir: &PathBuf, current_theme: Option<&str>) -> R
     Ok(themes)
 }
 
-/// Hardcoded list of Omarchy themes from awesome-omarchy
+/// Snapshot of the awesome-omarchy theme list, used when the live README
+/// can't be fetched
 /// Source: https://github.com/aorumbayev/awesome-omarchy
 pub fn get_awesome_omarchy_themes() -> Vec<Theme> {
     let themes_data = vec![
@@ -517,68 +652,219 @@ pub fn get_awesome_omarchy_themes() -> Vec<Theme> {
         .collect()
 }
 
-/// Fetch themes - uses hardcoded list from awesome-omarchy
-pub async fn fetch_github_themes() -> Result<Vec<Theme>> {
-    // Return the curated list from awesome-omarchy
-    // This is more reliable than GitHub API search which may miss themes
-    Ok(get_awesome_omarchy_themes())
-}
+/// Topic search results kept per query unless themes.toml sets `search_cap`.
+/// The search API itself stops at 1000.
+pub const DEFAULT_SEARCH_CAP: usize = 300;
 
-/// Fetch themes from GitHub API (fallback/additional)
-pub async fn fetch_github_api_themes() -> Result<Vec<Theme>> {
-    let client = reqwest::Client::new();
-
-    let response = client
-        .get("https://api.github.com/search/repositories")
-        .query(&[
-            ("q", "topic:omarchy-theme"),
-            ("sort", "stars"),
-            ("order", "desc"),
-            ("per_page", "100"),
-        ])
-        .header("Accept", "application/vnd.github+json")
-        .header("User-Agent", "cruzalex-themes/0.1")
-        .send()
-        .await?;
-
-    let result: GitHubSearchResult = response.json().await?;
-
-    Ok(result.items.into_iter().map(|repo| Theme::from_github(&repo)).collect())
+/// A GitHub repository search the catalogue pulls from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TopicSearch {
+    query: String,
+    /// Keep only repos whose name contains this (the topic alone is too broad)
+    name_filter: Option<String>,
 }
 
-/// Fetch GitHub stars for a specific theme repo
-/// URL should be in format: https://github.com/owner/repo
-pub async fn fetch_theme_stars(github_url: &str) -> Result<u32> {
-    // Extract owner/repo from URL
-    let url = github_url.trim_end_matches(".git");
-    let parts: Vec<&str> = url.split("github.com/").collect();
-    if parts.len() != 2 {
-        anyhow::bail!("Invalid GitHub URL");
+impl TopicSearch {
+    /// Repos tagged `topic`, optionally only those with `name_filter` in the name
+    pub fn topic(topic: &str, name_filter: Option<&str>) -> Self {
+        let name_filter = name_filter.map(str::to_lowercase);
+        let query = match &name_filter {
+            Some(needle) => format!("topic:{} {} in:name", topic, needle),
+            None => format!("topic:{}", topic),
+        };
+        Self { query, name_filter }
+    }
+
+    /// The topics the community tags Omarchy themes with
+    pub fn defaults() -> Vec<Self> {
+        vec![
+            Self::topic("omarchy-theme", None),
+            Self::topic("omarchy-themes", None),
+            Self::topic("hyprland-theme", Some("omarchy")),
+        ]
     }
-    let repo_path = parts[1];
+}
 
+/// Fetch themes from GitHub topic searches, with a note for each search
+/// that failed
+///
+/// Each search follows pagination up to `cap` results. A failing search is
+/// skipped and the rest still run, except after a rate limit, which every
+/// later search would hit too. Only every search failing is an error.
+pub async fn fetch_github_api_themes(
+    searches: &[TopicSearch],
+    cap: usize,
+) -> Result<(Vec<Theme>, Vec<String>)> {
     let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(5))
+        .timeout(std::time::Duration::from_secs(15))
         .build()?;
+    let mut seen = HashSet::new();
+    let mut themes = Vec::new();
+    let mut failures = Vec::new();
+    let mut first_error = None;
+    let mut any_ok = false;
+
+    for (i, search) in searches.iter().enumerate() {
+        let e = match search_repositories(&client, &search.query, cap).await {
+            Ok(repos) => {
+                any_ok = true;
+                themes.extend(collect_search(&mut seen, search, repos));
+                continue;
+            }
+            Err(e) => e,
+        };
+        let rate_limited = matches!(e, github::GitHubError::RateLimited(_));
+        failures.push(format!("`{}`: {}", search.query, e));
+        first_error.get_or_insert(e);
+        if rate_limited {
+            let skipped = searches.len() - i - 1;
+            if skipped > 0 {
+                failures.push(format!("{} more search(es) not run", skipped));
+            }
+            break;
+        }
+    }
 
-    let api_url = format!("https://api.github.com/repos/{}", repo_path);
+    match first_error {
+        Some(e) if !any_ok => Err(e.into()),
+        _ => Ok((themes, failures)),
+    }
+}
+
+/// Themes from one search's repos, skipping names that don't pass the
+/// filter and repos already `seen` (by normalized URL)
+fn collect_search(seen: &mut HashSet<String>, search: &TopicSearch, repos: Vec<GitHubRepo>) -> Vec<Theme> {
+    repos
+        .into_iter()
+        .filter(|repo| {
+            search
+                .name_filter
+                .as_deref()
+                .is_none_or(|needle| repo.name.to_lowercase().contains(needle))
+        })
+        .filter(|repo| seen.insert(normalize_repo_url(&repo.clone_url)))
+        .map(|repo| Theme::from_github(&repo))
+        .collect()
+}
+
+/// All pages of a repository search, up to `cap` results. Only an error on
+/// the first page is returned; later ones end the search early.
+async fn search_repositories(
+    client: &reqwest::Client,
+    query: &str,
+    cap: usize,
+) -> Result<Vec<GitHubRepo>, github::GitHubError> {
+    let per_page = cap.clamp(1, 100).to_string();
+    let mut repos = Vec::new();
+
+    for page in 1.. {
+        let page = page.to_string();
+        let (result, _) = github::get_json::<GitHubSearchResult>(
+            client,
+            "https://api.github.com/search/repositories",
+            &[
+                ("q", query),
+                ("sort", "stars"),
+                ("order", "desc"),
+                ("per_page", &per_page),
+                ("page", &page),
+            ],
+            None,
+        )
+        .await;
+
+        // A later page failing (usually the rate limit) keeps what the
+        // earlier pages found
+        let result = match result {
+            Ok(result) => result,
+            Err(_) if !repos.is_empty() => break,
+            Err(e) => return Err(e),
+        };
+        let Fetched::Modified(result, _) = result else {
+            break;
+        };
+        let total = (result.total_count as usize).min(cap);
+        let got = result.items.len();
+        repos.extend(result.items);
+        if got == 0 || repos.len() >= total {
+            break;
+        }
+    }
 
-    let response = client
-        .get(&api_url)
-        .header("Accept", "application/vnd.github+json")
-        .header("User-Agent", "cruzalex-themes/0.1")
-        .send()
-        .await?;
+    repos.truncate(cap);
+    Ok(repos)
+}
+
+/// Comparable form of a repo URL: no trailing slash or `.git`, lowercase
+pub fn normalize_repo_url(url: &str) -> String {
+    url.trim()
+        .trim_end_matches('/')
+        .trim_end_matches(".git")
+        .to_lowercase()
+}
 
-    if !response.status().is_success() {
-        anyhow::bail!("GitHub API error: {}", response.status());
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repo(name: &str, url: &str) -> GitHubRepo {
+        serde_json::from_value(serde_json::json!({
+            "name": name,
+            "full_name": format!("someone/{}", name),
+            "clone_url": url,
+            "html_url": url.trim_end_matches(".git"),
+            "description": null,
+            "stargazers_count": 1,
+            "owner": { "login": "someone" },
+        }))
+        .unwrap()
     }
 
-    #[derive(Deserialize)]
-    struct RepoInfo {
-        stargazers_count: u32,
+    #[test]
+    fn search_results_are_filtered_and_deduplicated() {
+        let searches = TopicSearch::defaults();
+        assert_eq!(searches[2].query, "topic:hyprland-theme omarchy in:name");
+
+        let mut seen = HashSet::new();
+        let first = collect_search(
+            &mut seen,
+            &searches[0],
+            vec![repo("omarchy-nord-theme", "https://github.com/someone/omarchy-nord-theme.git")],
+        );
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0].name, "nord");
+
+        let hyprland = collect_search(
+            &mut seen,
+            &searches[2],
+            vec![
+                repo("omarchy-nord-theme", "https://github.com/Someone/omarchy-nord-theme/"),
+                repo("hyprland-dots", "https://github.com/someone/hyprland-dots.git"),
+                repo("Omarchy-Kanagawa", "https://github.com/someone/Omarchy-Kanagawa.git"),
+            ],
+        );
+        let names: Vec<&str> = hyprland.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["Omarchy-Kanagawa"]);
     }
 
-    let info: RepoInfo = response.json().await?;
-    Ok(info.stargazers_count)
+    #[test]
+    fn markers_override_the_background() {
+        let dir = tempfile::tempdir().unwrap();
+        let (dark_bg, light_bg) = (Some(Rgba::hex(0x1a1b26)), Some(Rgba::hex(0xe1e2e7)));
+        assert_eq!(classify_light(dir.path(), dark_bg), Some(false));
+        assert_eq!(classify_light(dir.path(), light_bg), Some(true));
+        // Neither a palette nor a marker: unknown, not dark
+        assert_eq!(classify_light(dir.path(), None), None);
+
+        std::fs::write(dir.path().join("dark.mode"), "").unwrap();
+        assert_eq!(classify_light(dir.path(), light_bg), Some(false));
+        assert_eq!(classify_light(dir.path(), None), Some(false));
+
+        std::fs::write(dir.path().join("light.mode"), "").unwrap();
+        assert_eq!(classify_light(dir.path(), dark_bg), Some(true));
+
+        assert_eq!(light_from_name("rose-pine-dawn"), Some(true));
+        assert_eq!(light_from_name("tokyo-night"), Some(false));
+        assert_eq!(light_from_name("twilight"), None);
+    }
 }
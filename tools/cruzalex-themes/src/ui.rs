@@ -1,6 +1,10 @@
 //! UI rendering
 
 use crate::app::App;
+use crate::catalogue::format_age;
+use crate::diagnostics::Severity;
+use crate::github;
+use crate::query::Query;
 use crate::theme::ThemeStatus;
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
@@ -14,12 +18,11 @@ use ratatui_image::StatefulImage;
 // Synthwave 80s palette — overrides terminal theme so the TUI chrome reads the
 // same regardless of which theme is currently active.
 const NEON_PINK: Color = Color::Rgb(255, 16, 240);
-const NEON_PINK_SOFT: Color = Color::Rgb(255, 113, 206);
 const NEON_CYAN: Color = Color::Rgb(1, 205, 254);
 const NEON_LIME: Color = Color::Rgb(5, 255, 161);
 const NEON_PURPLE: Color = Color::Rgb(185, 103, 255);
 const NEON_YELLOW: Color = Color::Rgb(255, 251, 150);
-const NEON_ORANGE: Color = Color::Rgb(255, 158, 100);
+const NEON_RED: Color = Color::Rgb(254, 68, 80);
 const PANEL_BG: Color = Color::Rgb(31, 13, 64);
 const MUTED: Color = Color::Rgb(164, 138, 212);
 
@@ -47,6 +50,15 @@ pub fn draw(f: &mut Frame, app: &mut App) {
     if app.zoom_open {
         draw_zoom_modal(f, app);
     }
+    if app.hooks_open {
+        draw_hooks_modal(f, app);
+    }
+    if app.plan_open {
+        draw_plan_modal(f, app);
+    }
+    if app.update_open {
+        draw_update_modal(f, app);
+    }
 }
 
 fn draw_about_modal(f: &mut Frame, app: &App) {
@@ -56,7 +68,7 @@ fn draw_about_modal(f: &mut Frame, app: &App) {
     let (active, installed, available, favorites) = app.counts();
     let total = app.themes.len();
 
-    let lines = vec![
+    let mut lines = vec![
         Line::from(vec![Span::styled(
             "cruzAlex Themes",
             Style::default().fg(NEON_PINK).add_modifier(Modifier::BOLD),
@@ -76,9 +88,13 @@ fn draw_about_modal(f: &mut Frame, app: &App) {
             "Theme sources",
             Style::default().fg(NEON_YELLOW).add_modifier(Modifier::BOLD),
         )]),
-        Line::from("  Pulled into this TUI:"),
-        Line::from("    • aorumbayev/awesome-omarchy (curated, hardcoded)"),
-        Line::from("    • GitHub topic:omarchy-theme (live search)"),
+        Line::from("  Pulled into this TUI (configure with [[source]] in themes.toml):"),
+    ];
+    if app.source_labels.is_empty() {
+        lines.push(Line::from("    • none fetched this session"));
+    }
+    lines.extend(app.source_labels.iter().map(|label| Line::from(format!("    • {}", label))));
+    lines.extend([
         Line::from("  Browse manually for more:"),
         Line::from("    • omarchythemes.com"),
         Line::from("    • Wheel-Smith/awesome-omarchy (built-in themes)"),
@@ -92,13 +108,19 @@ fn draw_about_modal(f: &mut Frame, app: &App) {
         Line::from("  Enter          Apply theme"),
         Line::from("  i              Install (for Available themes)"),
         Line::from("  x              Delete installed theme"),
+        Line::from("  U              Update installed theme (shows incoming commits)"),
         Line::from("  f              Toggle favorite"),
-        Line::from("  /              Search"),
-        Line::from("  Tab            Cycle filter (All/Installed/Available/Favorites)"),
-        Line::from("  s              Cycle sort (Name/Stars)"),
+        Line::from("  /              Search: fuzzy text, author:x stars:>50 is:light has:backgrounds"),
+        Line::from("                 -is:installed, bg:#1a1b26~10, accent:pink"),
+        Line::from("  Tab            Cycle filter (All/Installed/Available/Favorites/Light/Dark/Readable)"),
+        Line::from("  s              Cycle sort (Name/Stars/Updated)"),
         Line::from("  p              Toggle preview panel"),
+        Line::from("  m              Similar themes panel (1-9 to jump)"),
         Line::from("  z              Zoom preview"),
-        Line::from("  r              Refresh remote themes"),
+        Line::from("  h              Hook results of last apply"),
+        Line::from("  u              Revert a failed apply"),
+        Line::from("  d              Dry run: show what applying would change"),
+        Line::from("  r              Refresh remote themes and check installed ones for updates"),
         Line::from("  ?              About (this screen)"),
         Line::from("  q / Esc        Quit"),
         Line::from(""),
@@ -109,12 +131,16 @@ fn draw_about_modal(f: &mut Frame, app: &App) {
         Line::from(format!("  themes:  {}", app.themes_dir.display())),
         Line::from(format!("  config:  {}", app.config_dir.display())),
         Line::from(format!("  cache:   {}", app.cache_dir.display())),
+        Line::from(match github::token() {
+            Some(token) => format!("  GitHub:  token from {}", token.source),
+            None => "  GitHub:  unauthenticated (60 requests/h)".to_string(),
+        }),
         Line::from(""),
         Line::from(vec![Span::styled(
             "Press ? or Esc to close",
             Style::default().fg(MUTED),
         )]),
-    ];
+    ]);
 
     let block = Block::default()
         .title(" About ")
@@ -124,6 +150,205 @@ fn draw_about_modal(f: &mut Frame, app: &App) {
     f.render_widget(paragraph, area);
 }
 
+fn draw_hooks_modal(f: &mut Frame, app: &App) {
+    let area = centered_rect_pct(70, 80, f.area());
+    f.render_widget(Clear, area);
+
+    let Some(report) = &app.last_apply else {
+        return;
+    };
+
+    let failed = report.failed_hooks();
+    let summary = if failed == 0 {
+        Span::styled(
+            format!("All {} hooks succeeded", report.hooks.len()),
+            Style::default().fg(NEON_LIME),
+        )
+    } else {
+        Span::styled(
+            format!("{} of {} hooks failed", failed, report.hooks.len()),
+            Style::default().fg(NEON_RED).add_modifier(Modifier::BOLD),
+        )
+    };
+
+    let mut lines = vec![Line::from(summary), Line::from("")];
+    if report.hooks.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "No executable hooks found in hooks/theme-set.d",
+            Style::default().fg(MUTED),
+        )));
+    }
+
+    for hook in &report.hooks {
+        let (mark, color) = if hook.succeeded() {
+            ("✓", NEON_LIME)
+        } else {
+            ("✗", NEON_RED)
+        };
+        lines.push(Line::from(vec![
+            Span::styled(format!(" {} ", mark), Style::default().fg(color)),
+            Span::styled(
+                format!("{:<22}", hook.name),
+                Style::default().fg(if hook.succeeded() { Color::White } else { color }),
+            ),
+            Span::styled(format!("{:<14}", hook.outcome.label()), Style::default().fg(color)),
+            Span::styled(
+                format!("{:.2}s", hook.duration.as_secs_f64()),
+                Style::default().fg(MUTED),
+            ),
+        ]));
+
+        // Output tails only for failures — successful hooks are mostly noise
+        if !hook.succeeded() {
+            for line in hook.stderr.lines() {
+                lines.push(Line::from(Span::styled(
+                    format!("     {}", line),
+                    Style::default().fg(NEON_PINK),
+                )));
+            }
+            for line in hook.stdout.lines() {
+                lines.push(Line::from(Span::styled(
+                    format!("     {}", line),
+                    Style::default().fg(MUTED),
+                )));
+            }
+        }
+    }
+
+    lines.push(Line::from(""));
+    if let Some(previous) = report.revert_target() {
+        lines.push(Line::from(vec![
+            Span::styled("Press u ", Style::default().fg(NEON_YELLOW).add_modifier(Modifier::BOLD)),
+            Span::styled(
+                format!("to revert to '{}' and re-run its hooks", previous.display_name()),
+                Style::default().fg(NEON_YELLOW),
+            ),
+        ]));
+    }
+    lines.push(Line::from(Span::styled(
+        "Press h or Esc to close",
+        Style::default().fg(MUTED),
+    )));
+
+    let block = Block::default()
+        .title(format!(" Hooks — {} ", report.theme))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(if failed == 0 { NEON_CYAN } else { NEON_RED }));
+    let paragraph = Paragraph::new(lines).block(block).wrap(Wrap { trim: false });
+    f.render_widget(paragraph, area);
+}
+
+fn draw_plan_modal(f: &mut Frame, app: &App) {
+    let area = centered_rect_pct(80, 85, f.area());
+    f.render_widget(Clear, area);
+
+    let Some(plan) = &app.plan else {
+        return;
+    };
+
+    let heading = |text: String| {
+        Line::from(Span::styled(
+            text,
+            Style::default().fg(NEON_YELLOW).add_modifier(Modifier::BOLD),
+        ))
+    };
+    let action_line = |action: &crate::plan::PlanAction| {
+        let color = if action.is_disruptive() { NEON_RED } else { Color::White };
+        Line::from(Span::styled(
+            format!("     {}", action.describe()),
+            Style::default().fg(color),
+        ))
+    };
+
+    let mut lines = vec![heading("Switch".to_string())];
+    lines.extend(plan.core.iter().map(action_line));
+    lines.push(Line::from(""));
+    lines.push(heading(format!("Hooks ({})", plan.hooks.len())));
+
+    for hook in &plan.hooks {
+        let note = if !hook.known {
+            "  custom hook, effects unknown"
+        } else if hook.actions.is_empty() {
+            "  nothing for this theme"
+        } else {
+            ""
+        };
+        lines.push(Line::from(vec![
+            Span::styled(format!("  {}", hook.name), Style::default().fg(NEON_CYAN)),
+            Span::styled(note, Style::default().fg(MUTED)),
+        ]));
+        lines.extend(hook.actions.iter().map(action_line));
+    }
+
+    lines.push(Line::from(""));
+    let disruptive = plan.disruptive_count();
+    if disruptive > 0 {
+        lines.push(Line::from(Span::styled(
+            format!("{} running process(es) would be signalled or restarted", disruptive),
+            Style::default().fg(NEON_RED).add_modifier(Modifier::BOLD),
+        )));
+    }
+    lines.push(Line::from(Span::styled(
+        "Press Enter to apply, d or Esc to close",
+        Style::default().fg(MUTED),
+    )));
+
+    let block = Block::default()
+        .title(format!(" Dry run — {} ", plan.theme))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(NEON_YELLOW));
+    let paragraph = Paragraph::new(lines).block(block).wrap(Wrap { trim: false });
+    f.render_widget(paragraph, area);
+}
+
+fn draw_update_modal(f: &mut Frame, app: &App) {
+    let area = centered_rect_pct(80, 85, f.area());
+    f.render_widget(Clear, area);
+
+    let Some(incoming) = &app.incoming else {
+        return;
+    };
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            format!("{} new commit(s) on {}", incoming.commits.len(), incoming.upstream),
+            Style::default().fg(NEON_YELLOW).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+    ];
+    let now = chrono::Utc::now();
+    for commit in &incoming.commits {
+        let age = commit
+            .time
+            .map(|t| format!(", {}", crate::catalogue::format_age((now - t).to_std().unwrap_or_default())))
+            .unwrap_or_default();
+        lines.push(Line::from(vec![
+            Span::styled(format!("  {} ", commit.id), Style::default().fg(NEON_CYAN)),
+            Span::styled(commit.summary.clone(), Style::default().fg(Color::White)),
+            Span::styled(format!("  ({}{})", commit.author, age), Style::default().fg(MUTED)),
+        ]));
+    }
+
+    lines.push(Line::from(""));
+    if !incoming.fast_forward {
+        lines.push(Line::from(Span::styled(
+            "This clone has local commits; it can't be fast-forwarded",
+            Style::default().fg(NEON_RED).add_modifier(Modifier::BOLD),
+        )));
+    }
+    lines.push(Line::from(Span::styled(
+        "Press Enter to update, Esc to close",
+        Style::default().fg(MUTED),
+    )));
+
+    let block = Block::default()
+        .title(format!(" Update — {} ", incoming.theme))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(NEON_YELLOW));
+    let paragraph = Paragraph::new(lines).block(block).wrap(Wrap { trim: false });
+    f.render_widget(paragraph, area);
+}
+
 fn draw_zoom_modal(f: &mut Frame, app: &mut App) {
     let area = centered_rect_pct(85, 90, f.area());
     f.render_widget(Clear, area);
@@ -184,7 +409,13 @@ fn draw_header(f: &mut Frame, app: &App, area: Rect) {
         app.sort_mode.label()
     );
 
-    let loading = if app.loading { " [Loading...]" } else { "" };
+    let loading = if app.loading {
+        " [Loading...]"
+    } else if app.refreshing {
+        " [Refreshing...]"
+    } else {
+        ""
+    };
 
     let header = Paragraph::new(Line::from(vec![
         Span::styled(title, Style::default().fg(NEON_CYAN)),
@@ -201,22 +432,105 @@ fn draw_header(f: &mut Frame, app: &App, area: Rect) {
 
 /// Draw main content area
 fn draw_main(f: &mut Frame, app: &mut App, area: Rect) {
+    let mut constraints = vec![Constraint::Min(30)];
+    if app.show_similar {
+        constraints.push(Constraint::Length(34));
+    }
+    if app.show_preview {
+        constraints.push(Constraint::Percentage(50));
+    }
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(constraints)
+        .split(area);
+
+    draw_theme_list(f, app, chunks[0]);
+    let mut next = 1;
+    if app.show_similar {
+        draw_similar(f, app, chunks[next]);
+        next += 1;
+    }
     if app.show_preview {
-        // Split into list and preview
-        let chunks = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
-            .split(area);
-
-        draw_theme_list(f, app, chunks[0]);
-        draw_preview(f, app, chunks[1]);
+        draw_preview(f, app, chunks[next]);
+    }
+}
+
+/// Side panel with the closest palettes to the selected theme
+fn draw_similar(f: &mut Frame, app: &App, area: Rect) {
+    let block = Block::default()
+        .title(" Similar (1-9 jump) ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(NEON_CYAN));
+
+    let lines: Vec<Line> = if app.similar.is_empty() {
+        let msg = if app.selected_theme().is_some_and(|t| t.colors.is_some()) {
+            "No other themes with a palette"
+        } else {
+            "Selected theme has no palette"
+        };
+        vec![Line::from(Span::styled(msg, Style::default().fg(MUTED)))]
     } else {
-        draw_theme_list(f, app, area);
+        app.similar
+            .iter()
+            .filter_map(|(name, distance)| Some((app.themes.iter().find(|t| &t.name == name)?, distance)))
+            .enumerate()
+            .map(|(i, (theme, distance))| {
+                let swatch = |c: Option<crate::color::Rgba>| match c {
+                    Some(c) => Span::styled(" ", Style::default().bg(c.into())),
+                    None => Span::raw(" "),
+                };
+                let colors = theme.colors.as_ref();
+                let mut name = theme.display_name.clone();
+                if name.chars().count() > 20 {
+                    name = name.chars().take(19).collect::<String>() + "…";
+                }
+                Line::from(vec![
+                    Span::styled(format!("{} ", i + 1), Style::default().fg(NEON_YELLOW)),
+                    swatch(colors.and_then(|c| c.background)),
+                    swatch(colors.and_then(|c| c.accent)),
+                    Span::raw(" "),
+                    Span::styled(format!("{:<20}", name), Style::default().fg(Color::White)),
+                    Span::styled(format!("{:>5.1}", distance), Style::default().fg(MUTED)),
+                ])
+            })
+            .collect()
+    };
+
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// Theme name with fuzzy-matched characters picked out.
+///
+/// Underlined as well as colored so matches survive the selected row's
+/// highlight style.
+fn highlighted_name(name: &str, positions: &[usize]) -> Vec<Span<'static>> {
+    let plain = Style::default().fg(Color::White);
+    let matched = Style::default()
+        .fg(NEON_CYAN)
+        .add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+
+    let mut spans = Vec::new();
+    let mut run = String::new();
+    let mut run_matched = false;
+    for (i, c) in name.chars().enumerate() {
+        let is_match = positions.binary_search(&i).is_ok();
+        if is_match != run_matched && !run.is_empty() {
+            let style = if run_matched { matched } else { plain };
+            spans.push(Span::styled(std::mem::take(&mut run), style));
+        }
+        run_matched = is_match;
+        run.push(c);
+    }
+    if !run.is_empty() {
+        spans.push(Span::styled(run, if run_matched { matched } else { plain }));
     }
+    spans
 }
 
 /// Draw theme list with scrolling support
 fn draw_theme_list(f: &mut Frame, app: &mut App, area: Rect) {
+    let query = Query::parse(&app.search_query);
+
     // Pre-collect theme data to avoid borrow issues
     let theme_data: Vec<_> = app
         .filtered_themes
@@ -231,13 +545,15 @@ fn draw_theme_list(f: &mut Frame, app: &mut App, area: Rect) {
                 theme.background_count,
                 app.favorites.contains(&theme.name),
                 theme.stars,
+                theme.worst_diagnostic(),
+                app.updates.contains(&theme.name),
             )
         })
         .collect();
 
     let items: Vec<ListItem> = theme_data
         .iter()
-        .map(|(_, display_name, status, is_light, bg_count, is_fav, stars)| {
+        .map(|(_, display_name, status, is_light, bg_count, is_fav, stars, diag, has_update)| {
             // Favorite star
             let fav_icon = if *is_fav {
                 Span::styled("★ ", Style::default().fg(NEON_PINK))
@@ -251,12 +567,10 @@ fn draw_theme_list(f: &mut Frame, app: &mut App, area: Rect) {
                 ThemeStatus::Available => Span::styled("◌ ", Style::default().fg(MUTED)),
             };
 
-            let name = Span::styled(
-                display_name.as_str(),
-                Style::default().fg(Color::White),
-            );
+            let mut spans = vec![fav_icon, status_icon];
+            spans.extend(highlighted_name(display_name, &query.highlights(display_name)));
 
-            let light_icon = if *is_light {
+            let light_icon = if *is_light == Some(true) {
                 Span::styled(" [light]", Style::default().fg(NEON_YELLOW))
             } else {
                 Span::raw("")
@@ -281,7 +595,22 @@ fn draw_theme_list(f: &mut Frame, app: &mut App, area: Rect) {
                 Span::raw("")
             };
 
-            ListItem::new(Line::from(vec![fav_icon, status_icon, name, light_icon, bg_count_span, stars_span]))
+            // colors.toml problems
+            let diag_span = match diag {
+                Some(Severity::Error) => Span::styled(" ⚠", Style::default().fg(NEON_RED)),
+                Some(Severity::Warning) => Span::styled(" ⚠", Style::default().fg(NEON_YELLOW)),
+                None => Span::raw(""),
+            };
+
+            // Remote branch has moved on
+            let update_span = if *has_update {
+                Span::styled(" ⇡", Style::default().fg(NEON_CYAN))
+            } else {
+                Span::raw("")
+            };
+
+            spans.extend([light_icon, bg_count_span, stars_span, diag_span, update_span]);
+            ListItem::new(Line::from(spans))
         })
         .collect();
 
@@ -430,6 +759,43 @@ fn draw_preview(f: &mut Frame, app: &mut App, area: Rect) {
             ]));
         }
 
+        if let Some(description) = &theme.description {
+            info_lines.push(Line::from(Span::styled(
+                description.as_str(),
+                Style::default().fg(Color::White),
+            )));
+        }
+
+        if let Some(pushed_at) = theme.pushed_at {
+            let age = (chrono::Utc::now() - pushed_at).to_std().unwrap_or_default();
+            let branch = theme
+                .default_branch
+                .as_deref()
+                .map(|b| format!(" on {}", b))
+                .unwrap_or_default();
+            info_lines.push(Line::from(vec![
+                Span::styled("Updated: ", Style::default().fg(MUTED)),
+                Span::styled(
+                    format!("{}{}", format_age(age), branch),
+                    Style::default().fg(Color::White),
+                ),
+            ]));
+        }
+
+        if let Some(license) = &theme.license {
+            info_lines.push(Line::from(vec![
+                Span::styled("License: ", Style::default().fg(MUTED)),
+                Span::styled(license.as_str(), Style::default().fg(Color::White)),
+            ]));
+        }
+
+        if !theme.topics.is_empty() {
+            info_lines.push(Line::from(vec![
+                Span::styled("Topics: ", Style::default().fg(MUTED)),
+                Span::styled(theme.topics.join(", "), Style::default().fg(NEON_CYAN)),
+            ]));
+        }
+
         if theme.background_count > 0 {
             info_lines.push(Line::from(vec![
                 Span::styled("Backgrounds: ", Style::default().fg(MUTED)),
@@ -440,6 +806,52 @@ fn draw_preview(f: &mut Frame, app: &mut App, area: Rect) {
             ]));
         }
 
+        if let Some(report) = &theme.contrast {
+            let failed: Vec<_> = report.failures().collect();
+            let badge = if failed.is_empty() {
+                Span::styled("✓ passes WCAG AA", Style::default().fg(NEON_LIME))
+            } else {
+                Span::styled(
+                    format!("✗ {} of {} checks fail", failed.len(), report.checks.len()),
+                    Style::default().fg(NEON_RED),
+                )
+            };
+            info_lines.push(Line::from(vec![
+                Span::styled("Contrast: ", Style::default().fg(MUTED)),
+                badge,
+            ]));
+            for check in failed {
+                info_lines.push(Line::from(Span::styled(
+                    format!("  {} {:.1}:1 (needs {:.1})", check.label, check.ratio, check.min_ratio),
+                    Style::default().fg(NEON_RED),
+                )));
+            }
+        }
+
+        if !theme.diagnostics.is_empty() {
+            info_lines.push(Line::from(Span::styled(
+                "colors.toml:",
+                Style::default().fg(MUTED),
+            )));
+            for diag in &theme.diagnostics {
+                let color = match diag.severity {
+                    Severity::Error => NEON_RED,
+                    Severity::Warning => NEON_YELLOW,
+                };
+                info_lines.push(Line::from(Span::styled(
+                    format!(" ⚠ {}", diag.describe()),
+                    Style::default().fg(color),
+                )));
+            }
+        }
+
+        if let Some(source) = theme.palette_source {
+            info_lines.push(Line::from(vec![
+                Span::styled("Palette: ", Style::default().fg(MUTED)),
+                Span::styled(format!("from {}", source), Style::default().fg(Color::White)),
+            ]));
+        }
+
         let info = Paragraph::new(info_lines).wrap(Wrap { trim: true });
         f.render_widget(info, info_chunk);
     } else {
@@ -481,10 +893,10 @@ fn draw_color_palette(f: &mut Frame, colors: &crate::theme::ColorPalette, area:
         .iter()
         .filter_map(|(label, color)| {
             color.as_ref().map(|c| {
-                let rgb = parse_hex_color(c);
+                let rgb = Color::from(*c);
                 Span::styled(
                     format!(" {} ", label),
-                    Style::default().bg(rgb).fg(contrast_color(rgb)),
+                    Style::default().bg(rgb).fg(Color::from(c.contrast_text())),
                 )
             })
         })
@@ -498,10 +910,10 @@ fn draw_color_palette(f: &mut Frame, colors: &crate::theme::ColorPalette, area:
         .iter()
         .filter_map(|(label, color)| {
             color.as_ref().map(|c| {
-                let rgb = parse_hex_color(c);
+                let rgb = Color::from(*c);
                 Span::styled(
-                    format!("{}", label),
-                    Style::default().bg(rgb).fg(contrast_color(rgb)),
+                    label.to_string(),
+                    Style::default().bg(rgb).fg(Color::from(c.contrast_text())),
                 )
             })
         })
@@ -515,10 +927,10 @@ fn draw_color_palette(f: &mut Frame, colors: &crate::theme::ColorPalette, area:
         .iter()
         .filter_map(|(label, color)| {
             color.as_ref().map(|c| {
-                let rgb = parse_hex_color(c);
+                let rgb = Color::from(*c);
                 Span::styled(
-                    format!("{}", label),
-                    Style::default().bg(rgb).fg(contrast_color(rgb)),
+                    label.to_string(),
+                    Style::default().bg(rgb).fg(Color::from(c.contrast_text())),
                 )
             })
         })
@@ -531,42 +943,23 @@ fn draw_color_palette(f: &mut Frame, colors: &crate::theme::ColorPalette, area:
     f.render_widget(palette, area);
 }
 
-/// Parse hex color to RGB
-fn parse_hex_color(hex: &str) -> Color {
-    let hex = hex.trim_start_matches('#');
-    if hex.len() >= 6 {
-        if let (Ok(r), Ok(g), Ok(b)) = (
-            u8::from_str_radix(&hex[0..2], 16),
-            u8::from_str_radix(&hex[2..4], 16),
-            u8::from_str_radix(&hex[4..6], 16),
-        ) {
-            return Color::Rgb(r, g, b);
-        }
-    }
-    Color::White
-}
-
-/// Get contrasting text color
-fn contrast_color(bg: Color) -> Color {
-    if let Color::Rgb(r, g, b) = bg {
-        let luminance = (0.299 * r as f32 + 0.587 * g as f32 + 0.114 * b as f32) / 255.0;
-        if luminance > 0.5 {
-            Color::Black
-        } else {
-            Color::White
-        }
-    } else {
-        Color::White
-    }
-}
-
 /// Draw footer with keybindings and status
 fn draw_footer(f: &mut Frame, app: &App, area: Rect) {
-    let keybindings = "[j/k] Nav [Enter] Apply [i] Install [f] Fav [Tab] Filter [s] Sort [/] Search [z] Zoom [r] Refresh [?] About [q] Quit";
+    let keybindings = "[j/k] Nav [Enter] Apply [i] Install [f] Fav [Tab] Filter [s] Sort [/] Search [d] Dry run [m] Similar [z] Zoom [h] Hooks [r] Refresh [?] About [q] Quit";
 
     let status = app.status_message.as_deref().unwrap_or("");
 
+    // Kept up front so it stays visible when the keybindings overflow
+    let rate_limit = match app.rate_limit.filter(|l| l.active()) {
+        Some(limit) => Span::styled(
+            format!("rate limited until {} | ", limit.reset_time()),
+            Style::default().fg(NEON_RED),
+        ),
+        None => Span::raw(""),
+    };
+
     let footer = Paragraph::new(Line::from(vec![
+        rate_limit,
         Span::styled(keybindings, Style::default().fg(MUTED)),
         Span::styled(" | ", Style::default().fg(MUTED)),
         Span::styled(status, Style::default().fg(NEON_YELLOW)),
@@ -0,0 +1,286 @@
+//! Updating installed themes that are git clones
+//!
+//! Whether an update exists is checked against the remote's branch head
+//! without downloading anything ([`update_available`]). The full fetch only
+//! happens when the incoming commits are about to be shown ([`fetch`]), and
+//! updating is fast-forward only: a theme with local commits, or local edits
+//! the update would overwrite, is left alone and reported.
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use git2::{
+    build::CheckoutBuilder, Cred, CredentialType, Direction, FetchOptions, Oid, RemoteCallbacks,
+    Repository,
+};
+use std::path::Path;
+
+/// A commit the update would bring in
+#[derive(Debug, Clone)]
+pub struct Commit {
+    /// Abbreviated hash
+    pub id: String,
+    pub summary: String,
+    pub author: String,
+    pub time: Option<DateTime<Utc>>,
+}
+
+/// What fetching a theme's tracked branch found
+#[derive(Debug, Clone)]
+pub struct Incoming {
+    pub theme: String,
+    /// Remote branch, e.g. "origin/main"
+    pub upstream: String,
+    /// Newest first
+    pub commits: Vec<Commit>,
+    /// False when HEAD has commits the remote doesn't
+    pub fast_forward: bool,
+    /// The fetched branch head; updating moves HEAD exactly here
+    pub target: Oid,
+}
+
+/// The branch HEAD is on and the remote branch it follows
+struct Tracking {
+    local_ref: String,
+    remote: String,
+    remote_branch: String,
+}
+
+fn tracking(repo: &Repository) -> Result<Tracking> {
+    let head = repo.head().context("no commits checked out")?;
+    if !head.is_branch() {
+        bail!("HEAD is detached; check out a branch to update");
+    }
+    let local_ref = head.name().context("branch name is not UTF-8")?.to_string();
+    let local = head.shorthand().unwrap_or_default().to_string();
+
+    // Clones record what they follow; default to origin and the same name
+    let config = repo.config()?;
+    let remote = config
+        .get_string(&format!("branch.{}.remote", local))
+        .unwrap_or_else(|_| "origin".to_string());
+    let remote_branch = config
+        .get_string(&format!("branch.{}.merge", local))
+        .ok()
+        .and_then(|merge| merge.strip_prefix("refs/heads/").map(str::to_string))
+        .unwrap_or(local);
+    Ok(Tracking {
+        local_ref,
+        remote,
+        remote_branch,
+    })
+}
+
+/// Credentials for SSH remotes from the ssh agent. Offered once: libgit2
+/// asks again for as long as the callback keeps answering.
+fn callbacks<'a>() -> RemoteCallbacks<'a> {
+    let mut tried = false;
+    let mut callbacks = RemoteCallbacks::new();
+    callbacks.credentials(move |_url, username, allowed| {
+        if tried || !allowed.contains(CredentialType::SSH_KEY) {
+            return Err(git2::Error::from_str(
+                "no usable credentials (is ssh-agent running?)",
+            ));
+        }
+        tried = true;
+        Cred::ssh_key_from_agent(username.unwrap_or("git"))
+    });
+    callbacks
+}
+
+/// Is a git checkout we can update?
+pub fn is_git_theme(path: &Path) -> bool {
+    path.join(".git").exists()
+}
+
+/// Does the remote branch have commits HEAD lacks? Lists the remote's refs
+/// (like `git ls-remote`) without fetching.
+pub fn update_available(path: &Path) -> Result<bool> {
+    let repo = Repository::open(path)?;
+    let tracking = tracking(&repo)?;
+    let mut remote = repo.find_remote(&tracking.remote)?;
+    remote.connect_auth(Direction::Fetch, Some(callbacks()), None)?;
+    let wanted = format!("refs/heads/{}", tracking.remote_branch);
+    let remote_head = remote
+        .list()?
+        .iter()
+        .find(|head| head.name() == wanted)
+        .map(|head| head.oid())
+        .with_context(|| {
+            format!(
+                "{} has no branch {}",
+                tracking.remote, tracking.remote_branch
+            )
+        })?;
+
+    let head = repo.head()?.peel_to_commit()?.id();
+    if remote_head == head {
+        return Ok(false);
+    }
+    // Already part of HEAD means we're ahead, not behind
+    let known = repo.find_commit(remote_head).is_ok();
+    Ok(!known || !repo.graph_descendant_of(head, remote_head)?)
+}
+
+/// Fetch the tracked branch and list what updating would bring in
+pub fn fetch(path: &Path) -> Result<Incoming> {
+    let repo = Repository::open(path)?;
+    let tracking = tracking(&repo)?;
+    let upstream = format!("{}/{}", tracking.remote, tracking.remote_branch);
+    let refspec = format!(
+        "+refs/heads/{}:refs/remotes/{}",
+        tracking.remote_branch, upstream
+    );
+    repo.find_remote(&tracking.remote)?
+        .fetch(
+            &[&refspec],
+            Some(FetchOptions::new().remote_callbacks(callbacks())),
+            None,
+        )
+        .with_context(|| format!("fetching {}", upstream))?;
+
+    let target = repo
+        .find_reference(&format!("refs/remotes/{}", upstream))?
+        .peel_to_commit()?
+        .id();
+    let head = repo.head()?.peel_to_commit()?.id();
+
+    let mut walk = repo.revwalk()?;
+    walk.push(target)?;
+    walk.hide(head)?;
+    let commits = walk
+        .map(|oid| {
+            let commit = repo.find_commit(oid?)?;
+            let author = commit.author().name().unwrap_or_default().to_string();
+            Ok(Commit {
+                id: commit
+                    .as_object()
+                    .short_id()?
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string(),
+                summary: commit.summary().unwrap_or_default().to_string(),
+                author,
+                time: DateTime::from_timestamp(commit.time().seconds(), 0),
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(Incoming {
+        theme: path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        upstream,
+        commits,
+        fast_forward: target == head || repo.graph_descendant_of(target, head)?,
+        target,
+    })
+}
+
+/// Move the checked-out branch forward to `target` (from [`fetch`]),
+/// refusing anything but a fast-forward
+pub fn fast_forward(path: &Path, target: Oid) -> Result<()> {
+    let repo = Repository::open(path)?;
+    let tracking = tracking(&repo)?;
+    let head = repo.head()?.peel_to_commit()?.id();
+    if head == target {
+        return Ok(());
+    }
+    if !repo.graph_descendant_of(target, head)? {
+        bail!(
+            "local commits on {}; not a fast-forward",
+            tracking.local_ref
+        );
+    }
+
+    // Safe checkout fails instead of overwriting local edits
+    let commit = repo.find_commit(target)?;
+    repo.checkout_tree(commit.as_object(), Some(CheckoutBuilder::new().safe()))
+        .context("local changes would be overwritten")?;
+    repo.find_reference(&tracking.local_ref)?
+        .set_target(target, "cruzalex-themes: fast-forward")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn commit(repo: &Repository, file: &str, content: &str, message: &str) {
+        std::fs::write(repo.workdir().unwrap().join(file), content).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new(file)).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = git2::Signature::now("Designer", "designer@example.com").unwrap();
+        let parent = repo.head().ok().map(|h| h.peel_to_commit().unwrap());
+        let parents: Vec<&git2::Commit> = parent.iter().collect();
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &parents,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn fetches_and_fast_forwards_new_commits() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        let upstream = Repository::init(dir.join("upstream")).unwrap();
+        commit(
+            &upstream,
+            "colors.toml",
+            "background = \"#000000\"\n",
+            "Initial palette",
+        );
+        let theme = dir.join("theme");
+        Repository::clone(dir.join("upstream").to_str().unwrap(), &theme).unwrap();
+        assert!(!update_available(&theme).unwrap());
+
+        commit(
+            &upstream,
+            "colors.toml",
+            "background = \"#101010\"\n",
+            "Lift the background",
+        );
+        commit(&upstream, "preview.png", "png", "Add preview");
+        assert!(update_available(&theme).unwrap());
+
+        let incoming = fetch(&theme).unwrap();
+        let summaries: Vec<&str> = incoming
+            .commits
+            .iter()
+            .map(|c| c.summary.as_str())
+            .collect();
+        assert_eq!(summaries, ["Add preview", "Lift the background"]);
+        assert!(incoming.fast_forward);
+        assert_eq!(incoming.commits[0].author, "Designer");
+
+        fast_forward(&theme, incoming.target).unwrap();
+        assert!(std::fs::read_to_string(theme.join("colors.toml"))
+            .unwrap()
+            .contains("#101010"));
+        assert!(theme.join("preview.png").is_file());
+        assert!(fetch(&theme).unwrap().commits.is_empty());
+    }
+
+    #[test]
+    fn refuses_to_overwrite_local_work() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        let upstream = Repository::init(dir.join("upstream")).unwrap();
+        commit(&upstream, "colors.toml", "a\n", "Initial");
+        let theme_path = dir.join("theme");
+        let theme = Repository::clone(dir.join("upstream").to_str().unwrap(), &theme_path).unwrap();
+        commit(&upstream, "colors.toml", "b\n", "Upstream change");
+        commit(&theme, "kitty.conf", "local\n", "My tweak");
+
+        let incoming = fetch(&theme_path).unwrap();
+        assert_eq!(incoming.commits.len(), 1);
+        assert!(!incoming.fast_forward);
+        assert!(fast_forward(&theme_path, incoming.target).is_err());
+    }
+}